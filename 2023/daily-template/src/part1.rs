@@ -6,17 +6,3 @@ pub fn process(
 ) -> miette::Result<String, AocError> {
     todo!("day 01 - part 1");
 }
-
-#[cfg(test)]
-mod tests {
-    use pretty_assertions::{assert_eq, assert_ne};
-    use super::*;
-
-    #[test]
-    fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
-        Ok(())
-    }
-}
\ No newline at end of file
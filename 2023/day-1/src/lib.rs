@@ -3,4 +3,19 @@ pub mod custom_error;
 pub mod part1;
 pub mod part1_nom;
 pub mod part2;
-pub mod part2_nom;
\ No newline at end of file
+pub mod part2_nom;
+
+aoc_common::solutions! {
+    budgets: 1 => 1 ms, 2 => 5 ms;
+    // Longer lines rather than more of them, to show the cost of scanning
+    // a line in part 2.
+    scale_input: aoc_common::bench_harness::widen_lines;
+    part1: 1 => crate::part1::process_sequential,
+    #[cfg(feature = "parallel")]
+    part1_parallel: 1 => crate::part1::process_parallel,
+    part1_nom: 1 => crate::part1_nom::process,
+    part2: 2 => crate::part2::process_sequential,
+    #[cfg(feature = "parallel")]
+    part2_parallel: 2 => crate::part2::process_parallel,
+    part2_nom: 2 => crate::part2_nom::process,
+}
@@ -1,22 +1,45 @@
 use crate::custom_error::AocError;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
+fn calibration_value(line: &str) -> u32 {
+    u32::from(((line.chars().find(|b| b.is_ascii_digit()).unwrap() as u8) - b'0') * 10
+        + (line.chars().rev().find(|b| b.is_ascii_digit()).unwrap() as u8)
+        - b'0')
+}
+
 #[tracing::instrument]
-pub fn process(
+pub fn process_sequential(
     input: &str,
 ) -> miette::Result<String, AocError> {
     let result = input
         .split(|b| b == '\n')
-        .map(|line| {
-            u32::from(((line.chars().find(|b| b.is_ascii_digit()).unwrap() as u8) - b'0') * 10
-                + (line.chars().rev().find(|b| b.is_ascii_digit()).unwrap() as u8)
-                - b'0')
-        })
+        .map(calibration_value)
         .sum::<u32>();
 
     //u32::try_from(result).ok()
     Ok(result.to_string())
 }
 
+#[cfg(feature = "parallel")]
+#[tracing::instrument]
+pub fn process_parallel(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let result = input
+        .par_split('\n')
+        .map(calibration_value)
+        .sum::<u32>();
+
+    Ok(result.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,4 +51,31 @@ mod tests {
         assert_eq!("142", process(input)?);
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "parallel")]
+    #[rstest::rstest]
+    #[case::example(Some(include_str!("../example1.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(1))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_allocations() -> miette::Result<()> {
+        let Some(input) = aoc_common::real_input!(1) else {
+            return Ok(());
+        };
+        // Only the answer is allocated, however many lines there are.
+        aoc_common::allocations::assert_within(1, || process_sequential(&input))?;
+        Ok(())
+    }
+}
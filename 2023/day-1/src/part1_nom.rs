@@ -10,8 +10,9 @@ fn nom_parse_line(line: &str) -> IResult<&str, Vec<&str>> {
 }
 
 fn parse_nom_result(multidigit: Vec<&str>) -> u32 {
-    let first = multidigit.first().unwrap().parse::<u32>().unwrap();
-    let last = multidigit.last().unwrap().parse::<u32>().unwrap();
+    // digit1 takes runs like "12"; only the outermost digits count
+    let first = multidigit.first().unwrap().chars().next().unwrap().to_digit(10).unwrap();
+    let last = multidigit.last().unwrap().chars().last().unwrap().to_digit(10).unwrap();
     first*10 + last
 }
 
@@ -56,6 +57,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_digit_runs_use_outermost_digits() -> miette::Result<()> {
+        // digit1 reads "12" and "345" as whole runs, but the calibration
+        // value is still the first and last digit of the line.
+        let input = "a12b345c\n9x";
+        assert_eq!(vec!["12", "345"], nom_parse_line("a12b345c").unwrap().1);
+        assert_eq!("114", process(input)?);
+        assert_eq!(crate::part1::process(input)?, process(input)?);
+        Ok(())
+    }
+
     #[test]
     fn test_process() -> miette::Result<()> {
         let input = include_str!("../example1.txt");
@@ -1,15 +1,38 @@
 use crate::custom_error::AocError;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
+fn calibration_value(line: &str) -> u32 {
+    ((0..line.len()).find_map(|i| num(line, i)).unwrap() * 10
+        + (0..line.len()).rev().find_map(|i| num(line, i)).unwrap()) as u32
+}
+
 #[tracing::instrument]
-pub fn process(
+pub fn process_sequential(
     input: &str,
 ) -> miette::Result<String, AocError> {
     let result = input
         .split(|b| b == '\n')
-        .map(|line| {
-            ((0..line.len()).find_map(|i| num(line, i)).unwrap() * 10
-                + (0..line.len()).rev().find_map(|i| num(line, i)).unwrap()) as u32
-        })
+        .map(calibration_value)
+        .sum::<u32>();
+
+    Ok(result.to_string())
+}
+
+#[cfg(feature = "parallel")]
+#[tracing::instrument]
+pub fn process_parallel(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let result = input
+        .par_split('\n')
+        .map(calibration_value)
         .sum::<u32>();
 
     Ok(result.to_string())
@@ -44,4 +67,31 @@ mod tests {
         assert_eq!("281", process(input)?);
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "parallel")]
+    #[rstest::rstest]
+    #[case::example(Some(include_str!("../example2.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(2))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_allocations() -> miette::Result<()> {
+        let Some(input) = aoc_common::real_input!(2) else {
+            return Ok(());
+        };
+        // Only the answer is allocated, however many lines there are.
+        aoc_common::allocations::assert_within(1, || process_sequential(&input))?;
+        Ok(())
+    }
+}
@@ -1,10 +1,6 @@
 use day_2::part2::process;
-use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    let file = include_str!("../../input2.txt");
-    let result = process(file).context("process part 2")?;
-    println!("{result}");
-    Ok(())
+    aoc_common::part_main!(day_2, 2, process)
 }
\ No newline at end of file
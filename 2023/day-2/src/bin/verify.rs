@@ -0,0 +1,10 @@
+use aoc_common::allocations::CountingAllocator;
+
+/// Counts allocations, so the peak heap of every run is reported.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs every variant on every input set and checks the answers.
+fn main() -> miette::Result<()> {
+    aoc_common::verify::main(env!("CARGO_MANIFEST_DIR"), day_2::SOLUTIONS)
+}
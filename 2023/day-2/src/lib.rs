@@ -5,4 +5,24 @@ pub mod part1_nom;
 pub mod part1_struct;
 pub mod part2;
 pub mod part2_nom;
-pub mod part2_struct;
\ No newline at end of file
+pub mod part2_struct;
+
+aoc_common::solutions! {
+    budgets: 1 => 1 ms, 2 => 1 ms;
+    part1: 1 => crate::part1::process_sequential,
+    #[cfg(feature = "parallel")]
+    part1_parallel: 1 => crate::part1::process_parallel,
+    part1_nom: 1 => crate::part1_nom::process {
+        parse: crate::part1_nom::parse_games,
+        solve: crate::part1_nom::solve,
+    },
+    part1_struct: 1 => crate::part1_struct::process,
+    part2: 2 => crate::part2::process_sequential,
+    #[cfg(feature = "parallel")]
+    part2_parallel: 2 => crate::part2::process_parallel,
+    part2_nom: 2 => crate::part2_nom::process {
+        parse: crate::part2_nom::parse_games,
+        solve: crate::part2_nom::solve,
+    },
+    part2_struct: 2 => crate::part2_struct::process,
+}
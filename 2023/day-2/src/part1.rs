@@ -1,7 +1,15 @@
 use crate::custom_error::AocError;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
 #[tracing::instrument]
-pub fn process(
+pub fn process_sequential(
     input: &str,
 ) -> miette::Result<String, AocError> {
     let result: usize = input
@@ -21,6 +29,31 @@ pub fn process(
     Ok(result.to_string())
 }
 
+#[cfg(feature = "parallel")]
+#[tracing::instrument]
+pub fn process_parallel(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    // Game ids come from line order, so the lines are collected to get an
+    // indexed parallel iterator that can still be enumerated.
+    let lines = input.lines().collect::<Vec<_>>();
+    let result: usize = lines
+        .par_iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let draws = parse_game(line);
+            (idx + 1, draws )
+        })
+        .filter(|(_, game_draws)| {
+            game_draws.iter()
+            .all(|draw| draw[0] <= 12 && draw[1] <= 13 && draw[2] <= 14)
+        })
+        .map(|(game_id, _)| game_id)
+        .sum();
+
+    Ok(result.to_string())
+}
+
 fn parse_game(game_line: &str) -> Vec<[u32; 3]> {
     let (_, game) = game_line.split_once(": ").unwrap(); 
     // let (_, id) = meta.split_once("Game ").unwrap();
@@ -62,4 +95,21 @@ mod tests {
         assert_eq!("8", process(input)?);
         Ok(())
     }
+
+    #[cfg(feature = "parallel")]
+    #[rstest]
+    #[case::example(Some(include_str!("../example1.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(1))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
 }
\ No newline at end of file
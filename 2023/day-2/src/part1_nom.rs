@@ -15,8 +15,18 @@ use nom::{
 pub fn process(
     input: &str,
 ) -> miette::Result<String, AocError> {
+    solve(&parse_games(input)?)
+}
+
+pub fn parse_games(
+    input: &str,
+) -> miette::Result<Vec<Game>, AocError> {
     let (_, games) = parse(input).expect("should parse");
-    let result = games.into_iter().filter(|game| {
+    Ok(games)
+}
+
+pub fn solve(games: &[Game]) -> miette::Result<String, AocError> {
+    let result = games.iter().filter(|game| {
         game.draws
             .iter()
             .all(|draw| draw.is_valid())
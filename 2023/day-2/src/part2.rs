@@ -2,8 +2,16 @@ use std::cmp;
 
 use crate::custom_error::AocError;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
 #[tracing::instrument]
-pub fn process(
+pub fn process_sequential(
     input: &str,
 ) -> miette::Result<String, AocError> {
     let result: u32 = input
@@ -21,6 +29,22 @@ pub fn process(
     Ok(result.to_string())
 }
 
+#[cfg(feature = "parallel")]
+#[tracing::instrument]
+pub fn process_parallel(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let result: u32 = input
+        .par_lines()
+        .map(parse_game)
+        .map(|draw| {
+            draw.iter().product::<u32>()
+        })
+        .sum();
+
+    Ok(result.to_string())
+}
+
 fn parse_game(game_line: &str) -> [u32; 3] {
     let (_, game) = game_line.split_once(": ").unwrap(); 
     // let (_, id) = meta.split_once("Game ").unwrap();
@@ -69,4 +93,21 @@ mod tests {
         assert_eq!("2286", process(input)?);
         Ok(())
     }
+
+    #[cfg(feature = "parallel")]
+    #[rstest]
+    #[case::example(Some(include_str!("../example2.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(2))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -8,8 +8,18 @@ use crate::custom_error::AocError;
 pub fn process(
     input: &str,
 ) -> miette::Result<String, AocError> {
+    solve(&parse_games(input)?)
+}
+
+pub fn parse_games(
+    input: &str,
+) -> miette::Result<Vec<Game>, AocError> {
     let (_, games) = parse(input).expect("should parse");
-    let result: u32 = games.into_iter()
+    Ok(games)
+}
+
+pub fn solve(games: &[Game]) -> miette::Result<String, AocError> {
+    let result: u32 = games.iter()
         .map(|game| {
             game.get_product()
         })
@@ -3,4 +3,24 @@ pub mod custom_error;
 pub mod part1;
 pub mod part1_nom;
 pub mod part2;
-pub mod part2_nom;
\ No newline at end of file
+pub mod part2_nom;
+
+aoc_common::solutions! {
+    budgets: 1 => 5 ms, 2 => 50 ms;
+    part1: 1 => crate::part1::process {
+        parse: crate::part1::parse_schematic,
+        solve: crate::part1::solve,
+    },
+    part1_nom: 1 => crate::part1_nom::process {
+        parse: crate::part1_nom::parse,
+        solve: crate::part1_nom::solve,
+    },
+    part2: 2 => crate::part2::process {
+        parse: crate::part2::parse_schematic,
+        solve: crate::part2::solve,
+    },
+    part2_nom: 2 => crate::part2_nom::process {
+        parse: crate::part2_nom::parse,
+        solve: crate::part2_nom::solve,
+    },
+}
@@ -6,8 +6,12 @@ use crate::custom_error::AocError;
 pub fn process(
     input: &str,
 ) -> miette::Result<String, AocError> {
-    let engine_schematic = parse_schematic(input).unwrap();
+    solve(&parse_schematic(input)?)
+}
 
+pub fn solve(
+    engine_schematic: &EngineSchematic,
+) -> miette::Result<String, AocError> {
     let mut total = 0;
     'next_gear: for gear in &engine_schematic.gears {
         let mut matches = Vec::new();
@@ -27,7 +31,7 @@ pub fn process(
     Ok(total.to_string())
 }
 
-fn parse_schematic(input: &str) -> miette::Result<EngineSchematic, AocError> {
+pub fn parse_schematic(input: &str) -> miette::Result<EngineSchematic, AocError> {
     let mut engine_schematic = EngineSchematic {
         part_numbers: Vec::new(),
         symbols: HashSet::new(),
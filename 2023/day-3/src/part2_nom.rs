@@ -17,7 +17,7 @@ type Span<'a> = LocatedSpan<&'a str>;
 type SpanIVec2<'a> = LocatedSpan<&'a str, IVec2>;
 
 #[derive(Debug, PartialEq)]
-enum Value<'a> {
+pub enum Value<'a> {
     Empty,
     Symbol(SpanIVec2<'a>),
     Number(SpanIVec2<'a>),
@@ -74,8 +74,18 @@ const POSITIONS: [IVec2; 8] = [
 pub fn process(
     input: &str,
 ) -> miette::Result<String, AocError> {
-    let objects = parse_grid(Span::new(input)).unwrap().1;
+    solve(&parse(input)?)
+}
 
+pub fn parse(
+    input: &str,
+) -> miette::Result<Vec<Value<'_>>, AocError> {
+    Ok(parse_grid(Span::new(input)).unwrap().1)
+}
+
+pub fn solve(
+    objects: &[Value],
+) -> miette::Result<String, AocError> {
     let number_map = objects
         .iter()
         .filter_map(|value| match value {
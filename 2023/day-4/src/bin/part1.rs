@@ -1,10 +1,6 @@
 use day_4::part1::process;
-use miette::Context;
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    let file = include_str!("../../input1.txt");
-    let result = process(file).context("process part 1")?;
-    println!("{result}");
-    Ok(())
+    aoc_common::part_main!(day_4, 1, process)
 }
\ No newline at end of file
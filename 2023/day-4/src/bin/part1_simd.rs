@@ -0,0 +1,6 @@
+use day_4::part1_simd::process;
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    aoc_common::part_main!(day_4, 1, process)
+}
\ No newline at end of file
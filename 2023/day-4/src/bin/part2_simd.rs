@@ -0,0 +1,6 @@
+use day_4::part2_simd::process;
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    aoc_common::part_main!(day_4, 2, process)
+}
\ No newline at end of file
@@ -1,4 +1,25 @@
 pub mod custom_error;
 
 pub mod part1;
-pub mod part2;
\ No newline at end of file
+pub mod part1_simd;
+pub mod part2;
+pub mod part2_simd;
+mod simd;
+
+aoc_common::solutions! {
+    budgets: 1 => 2 ms, 2 => 2 ms;
+    part1: 1 => crate::part1::process_sequential {
+        parse: crate::part1::parse_cards,
+        solve: crate::part1::solve,
+    },
+    #[cfg(feature = "parallel")]
+    part1_parallel: 1 => crate::part1::process_parallel,
+    part1_simd: 1 => crate::part1_simd::process,
+    part2: 2 => crate::part2::process_sequential {
+        parse: crate::part2::parse_cards,
+        solve: crate::part2::solve,
+    },
+    #[cfg(feature = "parallel")]
+    part2_parallel: 2 => crate::part2::process_parallel,
+    part2_simd: 2 => crate::part2_simd::process,
+}
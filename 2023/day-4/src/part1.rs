@@ -2,12 +2,38 @@ use std::collections::HashSet;
 
 use crate::custom_error::AocError;
 
-struct Card {
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
+pub struct Card {
     winning_numbers: HashSet<i64>,
     chosen_numbers: HashSet<i64>,
 }
 
 impl Card {
+    fn parse(line: &str) -> Self {
+        let (_, nums) = line.split_once(": ").unwrap();
+        let (win, chose) = nums.split_once(" | ").unwrap();
+
+        let winning_numbers = win
+            .split_whitespace()
+            .map(|snum| snum.parse::<i64>().unwrap())
+            .collect::<HashSet<_>>();
+        let chosen_numbers = chose
+            .split_whitespace()
+            .map(|snum| snum.parse::<i64>().unwrap())
+            .collect::<HashSet<_>>();
+        Card {
+            winning_numbers,
+            chosen_numbers,
+        }
+    }
+
     fn count(&self) -> usize {
         self.winning_numbers
             .intersection(&self.chosen_numbers)
@@ -25,32 +51,40 @@ impl Card {
 }
 
 #[tracing::instrument]
-pub fn process(
+pub fn process_sequential(
     input: &str,
 ) -> miette::Result<String, AocError> {
+    solve(&parse_cards(input)?)
+}
+
+pub fn parse_cards(
+    input: &str,
+) -> miette::Result<Vec<Card>, AocError> {
     let mut cards: Vec<Card> = Vec::new();
 
     for line in input.split(|b| b == '\n') {
-        let (_, nums) = line.split_once(": ").unwrap();
-        let (win, chose) = nums.split_once(" | ").unwrap();
-
-        let winning_numbers = win
-            .split_whitespace()
-            .map(|snum| snum.parse::<i64>().unwrap())
-            .collect::<HashSet<_>>();
-        let chosen_numbers = chose
-            .split_whitespace()
-            .map(|snum| snum.parse::<i64>().unwrap())
-            .collect::<HashSet<_>>();
-        cards.push(Card {
-            winning_numbers,
-            chosen_numbers,
-        });
+        cards.push(Card::parse(line));
     }
+    Ok(cards)
+}
+
+pub fn solve(cards: &[Card]) -> miette::Result<String, AocError> {
     let result = cards.iter().map(Card::score).sum::<i64>();
     Ok(result.to_string())
 }
 
+#[cfg(feature = "parallel")]
+#[tracing::instrument]
+pub fn process_parallel(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let result = input
+        .par_split('\n')
+        .map(|line| Card::parse(line).score())
+        .sum::<i64>();
+    Ok(result.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -62,4 +96,21 @@ mod tests {
         assert_eq!("13", process(input)?);
         Ok(())
     }
+
+    #[cfg(feature = "parallel")]
+    #[rstest::rstest]
+    #[case::example(Some(include_str!("../example1.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(1))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,77 @@
+use aoc_common::scan;
+
+use crate::{custom_error::AocError, simd::Layout};
+
+#[tracing::instrument]
+pub fn process(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let mut lines = scan::lines(input).peekable();
+    let Some(first) = lines.peek() else {
+        return Ok(0.to_string());
+    };
+    let layout = Layout::detect(first)?;
+
+    let result = lines
+        .enumerate()
+        .map(|(idx, line)| {
+            layout.count(idx + 1, line).map(|count| match count {
+                0 => 0,
+                count => 1 << (count - 1),
+            })
+        })
+        .sum::<Result<u64, _>>()?;
+    Ok(result.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use crate::part1;
+    use rstest::rstest;
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = include_str!("../example1.txt");
+        assert_eq!("13", process(input)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("Card 1 41 48 | 83 86", 1)]
+    #[case("Card 1: 41 48 83 86", 1)]
+    #[case("Card 1: 41 48 | 83 86 6", 1)]
+    #[case("Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 30\r", 2)]
+    #[case("Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 30 1", 2)]
+    #[case("Card 1: 41 48 | 83 86\nCard 2: 13 32 61 | 30", 2)]
+    #[case("Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 xy", 2)]
+    #[case("Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 1 ", 2)]
+    #[case("Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61   ", 2)]
+    fn test_malformed(#[case] input: &str, #[case] expected: usize) {
+        let error = process(input).unwrap_err();
+        assert!(
+            matches!(error, AocError::MalformedInput { line, .. } if line == expected),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn test_matches_scalar() -> miette::Result<()> {
+        let Some(input) = aoc_common::real_input!(1) else {
+            return Ok(());
+        };
+        assert_eq!(part1::process(&input)?, process(&input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_allocations() -> miette::Result<()> {
+        let Some(input) = aoc_common::real_input!(1) else {
+            return Ok(());
+        };
+        // Only the answer is allocated, however many cards there are.
+        aoc_common::allocations::assert_within(1, || process(&input))?;
+        Ok(())
+    }
+}
@@ -2,26 +2,21 @@ use std::collections::HashSet;
 
 use crate::custom_error::AocError;
 
-struct Card {
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
+pub struct Card {
     winning_numbers: HashSet<i64>,
     chosen_numbers: HashSet<i64>,
 }
 
 impl Card {
-    fn count(&self) -> usize {
-        self.winning_numbers
-            .intersection(&self.chosen_numbers)
-            .count()
-    }
-}
-
-#[tracing::instrument]
-pub fn process(
-    input: &str,
-) -> miette::Result<String, AocError> {
-    let mut cards: Vec<Card> = Vec::new();
-
-    for line in input.split(|b| b == '\n') {
+    fn parse(line: &str) -> Self {
         let (_, nums) = line.split_once(": ").unwrap();
         let (win, chose) = nums.split_once(" | ").unwrap();
 
@@ -33,20 +28,66 @@ pub fn process(
             .split_whitespace()
             .map(|snum| snum.parse::<i64>().unwrap())
             .collect::<HashSet<_>>();
-        cards.push(Card {
+        Card {
             winning_numbers,
             chosen_numbers,
-        });
+        }
     }
-    let mut multiplier = vec![1usize; cards.len()];
 
-    for (index, card) in cards.iter().enumerate() {
-        let count = card.count();
+    fn count(&self) -> usize {
+        self.winning_numbers
+            .intersection(&self.chosen_numbers)
+            .count()
+    }
+}
+
+fn total_cards(counts: &[usize]) -> usize {
+    let mut multiplier = vec![1usize; counts.len()];
+
+    for (index, count) in counts.iter().enumerate() {
         for i in index + 1..index + 1 + count {
             multiplier[i] += multiplier[index];
         }
     }
-    let result = multiplier.iter().sum::<usize>();
+    multiplier.iter().sum::<usize>()
+}
+
+#[tracing::instrument]
+pub fn process_sequential(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    solve(&parse_cards(input)?)
+}
+
+pub fn parse_cards(
+    input: &str,
+) -> miette::Result<Vec<Card>, AocError> {
+    let mut cards: Vec<Card> = Vec::new();
+
+    for line in input.split('\n') {
+        cards.push(Card::parse(line));
+    }
+    Ok(cards)
+}
+
+pub fn solve(cards: &[Card]) -> miette::Result<String, AocError> {
+    let counts = cards.iter().map(Card::count).collect::<Vec<_>>();
+    let result = total_cards(&counts);
+    Ok(result.to_string())
+}
+
+/// Matching numbers are counted per card in parallel; copies cascade
+/// from earlier cards to later ones, so the totals stay sequential.
+#[cfg(feature = "parallel")]
+#[tracing::instrument]
+pub fn process_parallel(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let counts = input
+        .par_split('\n')
+        .map(|line| Card::parse(line).count())
+        .collect::<Vec<_>>();
+    let result = total_cards(&counts);
     Ok(result.to_string())
 }
 
@@ -61,4 +102,21 @@ mod tests {
         assert_eq!("30", process(input)?);
         Ok(())
     }
+
+    #[cfg(feature = "parallel")]
+    #[rstest::rstest]
+    #[case::example(Some(include_str!("../example2.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(2))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,77 @@
+use aoc_common::scan;
+
+use crate::{custom_error::AocError, simd::Layout};
+
+#[tracing::instrument]
+pub fn process(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let mut lines = scan::lines(input).peekable();
+    let Some(first) = lines.peek() else {
+        return Ok(0.to_string());
+    };
+    let layout = Layout::detect(first)?;
+    let counts = lines
+        .enumerate()
+        .map(|(idx, line)| layout.count(idx + 1, line).map(|count| count as usize))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut multiplier = vec![1usize; counts.len()];
+    for (index, count) in counts.iter().enumerate() {
+        // Cards never make copies past the end of the table.
+        for i in index + 1..(index + 1 + count).min(counts.len()) {
+            multiplier[i] += multiplier[index];
+        }
+    }
+    let result = multiplier.iter().sum::<usize>();
+    Ok(result.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use crate::part2;
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = include_str!("../example2.txt");
+        assert_eq!("30", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed() {
+        let error = process("Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61").unwrap_err();
+        assert!(
+            matches!(error, AocError::MalformedInput { line: 2, .. }),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn test_copies_stop_at_last_card() -> miette::Result<()> {
+        assert_eq!("3", process("Card 1: 41 48 | 83 41\nCard 2: 41 48 | 41 48")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_scalar() -> miette::Result<()> {
+        let Some(input) = aoc_common::real_input!(2) else {
+            return Ok(());
+        };
+        assert_eq!(part2::process(&input)?, process(&input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_allocations() -> miette::Result<()> {
+        let Some(input) = aoc_common::real_input!(2) else {
+            return Ok(());
+        };
+        // Two vectors of per-card state, grown a few times, and the answer;
+        // the scalar version allocates on every card.
+        aoc_common::allocations::assert_within(16, || process(&input))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! Scanning shared by the SIMD variants of both parts: every card is laid
+//! out the same way, so the numbers are read from fixed offsets.
+
+use aoc_common::scan;
+
+use crate::custom_error::AocError;
+
+/// Byte offsets shared by every card. Numbers are right-aligned in
+/// three byte cells (`" 41"`, `"  6"`), so once the `:` and `|` are
+/// found on the first line each number sits at a known position.
+/// Cards of another shape are rejected rather than misread.
+pub(crate) struct Layout {
+    colon: usize,
+    bar: usize,
+    len: usize,
+}
+
+fn malformed(line: usize, reason: impl Into<String>) -> AocError {
+    AocError::MalformedInput {
+        line,
+        reason: reason.into(),
+    }
+}
+
+impl Layout {
+    pub(crate) fn detect(line: &str) -> Result<Self, AocError> {
+        let colon = line
+            .find(':')
+            .ok_or_else(|| malformed(1, "has no `:` after the card number"))?;
+        let bar = line
+            .find('|')
+            .filter(|&bar| bar >= colon + 2)
+            .ok_or_else(|| malformed(1, "has no `|` after the winning numbers"))?;
+        let layout = Self {
+            colon,
+            bar,
+            len: line.len(),
+        };
+        if !(bar - 1 - (colon + 1)).is_multiple_of(3) || !(layout.len - bar - 1).is_multiple_of(3) {
+            return Err(malformed(1, "does not have its numbers in three byte cells"));
+        }
+        Ok(layout)
+    }
+
+    /// Bitset of the numbers (all below 100, right-aligned) in a run of
+    /// cells.
+    fn numbers(number: usize, cells: &[u8]) -> Result<u128, AocError> {
+        cells.chunks(3).try_fold(0, |set, cell| {
+            let digits = cell.trim_ascii_start();
+            let value = scan::parse_fixed(cell);
+            if value < 100 && !digits.is_empty() && digits.iter().all(u8::is_ascii_digit) {
+                Ok(set | 1 << value)
+            } else {
+                Err(malformed(
+                    number,
+                    format!(
+                        "has {:?} where a number below 100 should be",
+                        String::from_utf8_lossy(cell)
+                    ),
+                ))
+            }
+        })
+    }
+
+    /// Matching numbers on the card on line `number`.
+    pub(crate) fn count(&self, number: usize, line: &str) -> Result<u32, AocError> {
+        let line = line.as_bytes();
+        if line.len() != self.len || line[self.colon] != b':' || line[self.bar] != b'|' {
+            return Err(malformed(
+                number,
+                format!(
+                    "is not {} bytes with `:` and `|` where the first card has them",
+                    self.len
+                ),
+            ));
+        }
+        let winning = Self::numbers(number, &line[self.colon + 1..self.bar - 1])?;
+        let chosen = Self::numbers(number, &line[self.bar + 1..])?;
+        Ok((winning & chosen).count_ones())
+    }
+}
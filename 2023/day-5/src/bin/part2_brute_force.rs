@@ -0,0 +1,6 @@
+use day_5::part2_brute_force::process;
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    aoc_common::part_main!(day_5, 2, process)
+}
\ No newline at end of file
@@ -1,4 +1,31 @@
 pub mod custom_error;
 
 pub mod part1;
-pub mod part2;
\ No newline at end of file
+pub mod part2;
+pub mod part2_brute_force;
+
+/// The almanac with its seeds repeated `factor` times, for the scaling
+/// benchmarks; its maps stay as they are.
+pub fn scale_input(input: &str, factor: usize) -> String {
+    let (seeds, maps) = input.split_once('\n').unwrap_or((input, ""));
+    let seeds = seeds.trim_start_matches("seeds:").trim();
+    format!("seeds: {}\n{maps}", vec![seeds; factor].join(" "))
+}
+
+aoc_common::solutions! {
+    budgets: 1 => 1 ms, 2 => 2 ms;
+    scale_input: crate::scale_input;
+    part1: 1 => crate::part1::process_sequential {
+        parse: crate::part1::parse_almanac,
+        solve: crate::part1::solve,
+    },
+    #[cfg(feature = "parallel")]
+    part1_parallel: 1 => crate::part1::process_parallel,
+    part2: 2 => crate::part2::process_sequential {
+        parse: crate::part2::parse_almanac,
+        solve: crate::part2::solve,
+    },
+    #[cfg(feature = "parallel")]
+    part2_parallel: 2 => crate::part2::process_parallel,
+    part2_brute_force: 2 => crate::part2_brute_force::process [slow],
+}
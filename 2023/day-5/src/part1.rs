@@ -1,17 +1,133 @@
 use crate::custom_error::AocError;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
+#[derive(Debug)]
+struct Entry {
+    destination: u64,
+    source: u64,
+    length: u64,
+}
+
+#[derive(Debug)]
+struct Map {
+    entries: Vec<Entry>,
+}
+
+impl Map {
+    fn convert(&self, value: u64) -> u64 {
+        self.entries
+            .iter()
+            .find(|entry| {
+                (entry.source..entry.source + entry.length).contains(&value)
+            })
+            .map_or(value, |entry| entry.destination + value - entry.source)
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub(crate) seeds: Vec<u64>,
+    maps: Vec<Map>,
+}
+
+impl Almanac {
+    pub(crate) fn location(&self, seed: u64) -> u64 {
+        self.maps.iter().fold(seed, |value, map| map.convert(value))
+    }
+}
+
+pub fn parse_almanac(
+    input: &str,
+) -> miette::Result<Almanac, AocError> {
+    let mut sections = input.split("\n\n");
+    let seeds = sections
+        .next()
+        .unwrap()
+        .trim_start_matches("seeds:")
+        .split_whitespace()
+        .map(|seed| seed.parse::<u64>().unwrap())
+        .collect();
+    let maps = sections
+        .map(|section| Map {
+            entries: section
+                .lines()
+                .skip(1)
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    let mut nums = line
+                        .split_whitespace()
+                        .map(|num| num.parse::<u64>().unwrap());
+                    Entry {
+                        destination: nums.next().unwrap(),
+                        source: nums.next().unwrap(),
+                        length: nums.next().unwrap(),
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(Almanac { seeds, maps })
+}
+
+#[tracing::instrument]
+pub fn process_sequential(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    solve(&parse_almanac(input)?)
+}
+
+pub fn solve(almanac: &Almanac) -> miette::Result<String, AocError> {
+    let result = almanac
+        .seeds
+        .iter()
+        .map(|&seed| almanac.location(seed))
+        .min()
+        .unwrap();
+    Ok(result.to_string())
+}
+
+#[cfg(feature = "parallel")]
 #[tracing::instrument]
-pub fn process(
+pub fn process_parallel(
     input: &str,
 ) -> miette::Result<String, AocError> {
-    todo!("day 01 - part 1");
+    let almanac = parse_almanac(input)?;
+    let result = almanac
+        .seeds
+        .par_iter()
+        .map(|&seed| almanac.location(seed))
+        .min()
+        .unwrap();
+    Ok(result.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
     use super::*;
 
+    #[rstest]
+    #[case(79, 82)]
+    #[case(14, 43)]
+    #[case(55, 86)]
+    #[case(13, 35)]
+    fn test_location(
+        #[case] seed: u64, #[case] expected: u64,
+    ) -> miette::Result<()> {
+        let almanac = parse_almanac(include_str!("../example1.txt"))?;
+        assert_eq!(expected, almanac.location(seed));
+        Ok(())
+    }
+
     #[test]
     fn test_process() -> miette::Result<()> {
         let input = "seeds: 79 14 55 13
@@ -50,4 +166,21 @@ humidity-to-location map:
         assert_eq!("35", process(input)?);
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "parallel")]
+    #[rstest]
+    #[case::example(Some(include_str!("../example1.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(1))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
+}
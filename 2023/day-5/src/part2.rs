@@ -1,22 +1,192 @@
+use std::ops::Range;
+
 use crate::custom_error::AocError;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub use self::process_parallel as process;
+#[cfg(not(feature = "parallel"))]
+pub use self::process_sequential as process;
+
+#[derive(Debug)]
+struct Entry {
+    destination: u64,
+    source: Range<u64>,
+}
+
+#[derive(Debug)]
+struct Map {
+    entries: Vec<Entry>,
+}
+
+impl Map {
+    /// Converts whole ranges at once, splitting a range wherever it
+    /// straddles the edge of an entry's source range.
+    fn convert_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        let mut converted = Vec::new();
+        let mut pending = ranges;
+
+        for entry in &self.entries {
+            let mut unmatched = Vec::new();
+            for range in pending {
+                let start = range.start.max(entry.source.start);
+                let end = range.end.min(entry.source.end);
+                if start >= end {
+                    unmatched.push(range);
+                    continue;
+                }
+                let offset = entry.destination;
+                converted.push(
+                    start - entry.source.start + offset
+                        ..end - entry.source.start + offset,
+                );
+                if range.start < start {
+                    unmatched.push(range.start..start);
+                }
+                if end < range.end {
+                    unmatched.push(end..range.end);
+                }
+            }
+            pending = unmatched;
+        }
+
+        // Anything no entry matched keeps its number.
+        converted.extend(pending);
+        converted
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac {
+    seed_ranges: Vec<Range<u64>>,
+    maps: Vec<Map>,
+}
+
+impl Almanac {
+    fn lowest_location(&self, seeds: Range<u64>) -> u64 {
+        self.maps
+            .iter()
+            .fold(vec![seeds], |ranges, map| map.convert_ranges(ranges))
+            .iter()
+            .map(|range| range.start)
+            .min()
+            .unwrap()
+    }
+}
+
+pub fn parse_almanac(
+    input: &str,
+) -> miette::Result<Almanac, AocError> {
+    let mut sections = input.split("\n\n");
+    let seeds = sections
+        .next()
+        .unwrap()
+        .trim_start_matches("seeds:")
+        .split_whitespace()
+        .map(|seed| seed.parse::<u64>().unwrap())
+        .collect::<Vec<_>>();
+    let seed_ranges = seeds
+        .chunks(2)
+        .map(|pair| pair[0]..pair[0] + pair[1])
+        .collect();
+    let maps = sections
+        .map(|section| Map {
+            entries: section
+                .lines()
+                .skip(1)
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    let mut nums = line
+                        .split_whitespace()
+                        .map(|num| num.parse::<u64>().unwrap());
+                    let destination = nums.next().unwrap();
+                    let source = nums.next().unwrap();
+                    let length = nums.next().unwrap();
+                    Entry {
+                        destination,
+                        source: source..source + length,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(Almanac { seed_ranges, maps })
+}
+
 #[tracing::instrument]
-pub fn process(
+pub fn process_sequential(
     input: &str,
 ) -> miette::Result<String, AocError> {
-    todo!("day 01 - part 2");
+    solve(&parse_almanac(input)?)
+}
+
+pub fn solve(almanac: &Almanac) -> miette::Result<String, AocError> {
+    let result = almanac
+        .seed_ranges
+        .iter()
+        .map(|seeds| almanac.lowest_location(seeds.clone()))
+        .min()
+        .unwrap();
+    Ok(result.to_string())
+}
+
+#[cfg(feature = "parallel")]
+#[tracing::instrument]
+pub fn process_parallel(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let almanac = parse_almanac(input)?;
+    let result = almanac
+        .seed_ranges
+        .par_iter()
+        .map(|seeds| almanac.lowest_location(seeds.clone()))
+        .min()
+        .unwrap();
+    Ok(result.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
     use super::*;
 
+    #[rstest]
+    #[case(79..80, 82)]
+    #[case(79..93, 46)]
+    #[case(55..68, 56)]
+    fn test_lowest_location(
+        #[case] seeds: Range<u64>, #[case] expected: u64,
+    ) -> miette::Result<()> {
+        let almanac = parse_almanac(include_str!("../example2.txt"))?;
+        assert_eq!(expected, almanac.lowest_location(seeds));
+        Ok(())
+    }
+
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        let input = include_str!("../example2.txt");
+        assert_eq!("46", process(input)?);
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "parallel")]
+    #[rstest]
+    #[case::example(Some(include_str!("../example2.txt").to_string()))]
+    #[case::input(aoc_common::real_input!(2))]
+    fn test_parallel_matches_sequential(
+        #[case] input: Option<String>,
+    ) -> miette::Result<()> {
+        let Some(input) = input else {
+            return Ok(());
+        };
+        assert_eq!(
+            process_sequential(&input)?,
+            process_parallel(&input)?
+        );
+        Ok(())
+    }
+}
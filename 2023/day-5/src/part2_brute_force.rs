@@ -0,0 +1,67 @@
+use aoc_common::{cancel, progress::Progress};
+
+use crate::{custom_error::AocError, part1::parse_almanac};
+
+/// Seeds converted between progress updates and cancellation checks.
+const REPORT_EVERY: u64 = 1 << 16;
+
+/// Converts every seed of every range one at a time. Kept as the naive
+/// baseline for `part2`, which converts whole ranges instead; on real input
+/// this takes minutes, so it reports its progress and stops when its run is
+/// cancelled.
+#[tracing::instrument]
+pub fn process(
+    input: &str,
+) -> miette::Result<String, AocError> {
+    let almanac = parse_almanac(input)?;
+    let token = cancel::current();
+    let progress = Progress::new(
+        almanac.seeds.chunks(2).map(|pair| pair[1]).sum(),
+    );
+
+    let mut result = u64::MAX;
+    for pair in almanac.seeds.chunks(2) {
+        let seeds = pair[0]..pair[0] + pair[1];
+        for (idx, seed) in seeds.enumerate() {
+            result = result.min(almanac.location(seed));
+            if idx as u64 % REPORT_EVERY == REPORT_EVERY - 1 {
+                token.check()?;
+                progress.inc(REPORT_EVERY);
+            }
+        }
+        progress.inc(pair[1] % REPORT_EVERY);
+    }
+
+    Ok(result.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aoc_common::runner::Runner;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = include_str!("../example2.txt");
+        assert_eq!("46", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_times_out() {
+        let Some(input) = aoc_common::real_input!(2) else {
+            return;
+        };
+        let runner = Runner::new().budget(Duration::from_millis(100));
+        assert!(matches!(
+            runner.run(&input, process),
+            Err(AocError::TimedOut {
+                progress: Some((done, total)),
+                ..
+            }) if done < total
+        ));
+    }
+}
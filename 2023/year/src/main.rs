@@ -0,0 +1,8 @@
+/// Runs every part of every day of the year and prints their answers and
+/// times, see `just run-year`.
+fn main() -> miette::Result<()> {
+    aoc_common::year::main(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/.."),
+        aoc_common::days![day_1, day_2, day_3, day_4, day_5],
+    )
+}
@@ -0,0 +1,11 @@
+use aoc_common::allocations::CountingAllocator;
+
+/// Counts allocations, so the peak heap of every run is reported.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs every variant on every input set and checks the answers.
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+    aoc_common::verify::main(env!("CARGO_MANIFEST_DIR"), {{crate_name}}::SOLUTIONS)
+}
@@ -1,4 +1,9 @@
 pub mod custom_error;
 
 pub mod part1;
-pub mod part2;
\ No newline at end of file
+pub mod part2;
+
+aoc_common::solutions! {
+    part1: 1 => crate::part1::process,
+    part2: 2 => crate::part2::process,
+}
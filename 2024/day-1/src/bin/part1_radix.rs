@@ -0,0 +1,7 @@
+use day_1::part1_radix::process;
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+    aoc_common::part_main!(day_1, 1, process)
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+use day_1::part1_simd::process;
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+    aoc_common::part_main!(day_1, 1, process)
+}
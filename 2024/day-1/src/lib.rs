@@ -2,4 +2,15 @@ pub mod custom_error;
 
 pub mod part1;
 pub mod part1_nom;
+pub mod part1_radix;
+pub mod part1_simd;
 pub mod part2;
+
+aoc_common::solutions! {
+    budgets: 1 => 2 ms;
+    part1: 1 => crate::part1::process,
+    part1_nom: 1 => crate::part1_nom::process,
+    part1_radix: 1 => crate::part1_radix::process,
+    part1_simd: 1 => crate::part1_simd::process,
+    part2: 2 => crate::part2::process,
+}
@@ -0,0 +1,236 @@
+use crate::custom_error::AocError;
+
+/// Bits consumed per LSD radix pass; 256 buckets keeps the
+/// histogram in L1 while five digit IDs need only three passes.
+const RADIX_BITS: u32 = 8;
+const BUCKETS: usize = 1 << RADIX_BITS;
+
+/// Column layout of the input, taken from the first line. Every
+/// line of the puzzle input has the same shape (`NNNNN   NNNNN\n`),
+/// so each number can be read at a fixed offset without scanning;
+/// lines of another shape are rejected rather than misread.
+#[derive(Debug, PartialEq, Eq)]
+struct Layout {
+    left_width: usize,
+    right_offset: usize,
+    line_len: usize,
+}
+
+impl Layout {
+    fn detect(input: &[u8]) -> Option<Self> {
+        let line_len = input
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap_or(input.len());
+        let line = &input[..line_len];
+        let left_width = line.iter().position(|&b| b == b' ')?;
+        let right_offset = left_width
+            + line[left_width..].iter().position(|&b| b != b' ')?;
+        Some(Self {
+            left_width,
+            right_offset,
+            line_len,
+        })
+    }
+
+    fn stride(&self) -> usize {
+        self.line_len + 1
+    }
+
+    /// The line in a chunk of `stride` bytes, if it is as long as
+    /// the first and ends the chunk.
+    fn line<'a>(&self, chunk: &'a [u8]) -> Option<&'a [u8]> {
+        match chunk.split_at_checked(self.line_len) {
+            Some((line, b"\n" | b"")) => Some(line),
+            _ => None,
+        }
+    }
+}
+
+fn malformed(line: usize, reason: impl Into<String>) -> AocError {
+    AocError::MalformedInput {
+        line,
+        reason: reason.into(),
+    }
+}
+
+#[inline(always)]
+fn parse_fixed(digits: &[u8], line: usize) -> Result<u32, AocError> {
+    digits.iter().try_fold(0, |acc, &b| match b {
+        b'0'..=b'9' => Ok(acc * 10 + u32::from(b - b'0')),
+        _ => Err(malformed(
+            line,
+            format!("has {:?} where a digit should be", char::from(b)),
+        )),
+    })
+}
+
+fn parse(input: &str) -> Result<(Vec<u32>, Vec<u32>), AocError> {
+    let bytes = input.as_bytes();
+    if bytes.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+    let layout = Layout::detect(bytes)
+        .ok_or_else(|| malformed(1, "is not two columns of numbers"))?;
+    let rows = bytes.len().div_ceil(layout.stride());
+    let mut left = Vec::with_capacity(rows);
+    let mut right = Vec::with_capacity(rows);
+
+    for (idx, chunk) in bytes.chunks(layout.stride()).enumerate() {
+        let number = idx + 1;
+        let line = layout.line(chunk).ok_or_else(|| {
+            malformed(
+                number,
+                format!("is not {} bytes long like the first", layout.line_len),
+            )
+        })?;
+        if line[layout.left_width..layout.right_offset]
+            .iter()
+            .any(|&b| b != b' ')
+        {
+            return Err(malformed(number, "has no spaces between its columns"));
+        }
+        left.push(parse_fixed(&line[..layout.left_width], number)?);
+        right.push(parse_fixed(&line[layout.right_offset..], number)?);
+    }
+
+    Ok((left, right))
+}
+
+/// LSD radix sort, skipping the passes above the highest set bit
+/// of the largest value.
+fn radix_sort(values: &mut Vec<u32>) {
+    let max = values.iter().copied().max().unwrap_or(0);
+    let significant_bits = u32::BITS - max.leading_zeros();
+    let mut scratch = vec![0u32; values.len()];
+
+    let mut shift = 0;
+    while shift < significant_bits {
+        let mut counts = [0usize; BUCKETS];
+        for &value in values.iter() {
+            counts[(value >> shift) as usize & (BUCKETS - 1)] += 1;
+        }
+        let mut offset = 0;
+        for count in counts.iter_mut() {
+            let bucket = *count;
+            *count = offset;
+            offset += bucket;
+        }
+        for &value in values.iter() {
+            let bucket = (value >> shift) as usize & (BUCKETS - 1);
+            scratch[counts[bucket]] = value;
+            counts[bucket] += 1;
+        }
+        std::mem::swap(values, &mut scratch);
+        shift += RADIX_BITS;
+    }
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String, AocError> {
+    let (mut left, mut right) = parse(input)?;
+
+    radix_sort(&mut left);
+    radix_sort(&mut right);
+
+    let result: u64 = std::iter::zip(left, right)
+        .map(|(l, r)| u64::from(l.abs_diff(r)))
+        .sum();
+
+    Ok(result.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn generate_lists(rows: usize, seed: u64) -> String {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        (0..rows)
+            .map(|_| {
+                format!(
+                    "{}   {}",
+                    rng.u32(10000..=99999),
+                    rng.u32(10000..=99999)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(
+            Some(Layout {
+                left_width: 5,
+                right_offset: 8,
+                line_len: 13,
+            }),
+            Layout::detect(b"80414   72092\n17250   26414")
+        );
+        assert_eq!(None, Layout::detect(b""));
+    }
+
+    #[rstest]
+    #[case(vec![])]
+    #[case(vec![3, 4, 2, 1, 3, 3])]
+    #[case(vec![99999, 10000, 54321, 12345, 10000])]
+    #[case(vec![u32::MAX, 0, 1 << 24, 1 << 16, 255, 256])]
+    fn test_radix_sort(#[case] mut values: Vec<u32>) {
+        let mut expected = values.clone();
+        expected.sort();
+        radix_sort(&mut values);
+        assert_eq!(expected, values);
+    }
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = include_str!("../example1.txt");
+        assert_eq!("11", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_trailing_newline() -> miette::Result<()> {
+        let input = format!("{}\n", include_str!("../example1.txt"));
+        assert_eq!("11", process(&input)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("80414   72092\r\n17250   26414\r", 1)]
+    #[case("80414   72092\n17250   2641", 2)]
+    #[case("80414   72092\n17250   264145", 2)]
+    #[case("80414   72092\n17250 9 26414", 2)]
+    #[case("80414   72092\n17250   26a14\n", 2)]
+    #[case("80414   72092\n17250   26414\n\n", 3)]
+    #[case("8041472092", 1)]
+    fn test_malformed(#[case] input: &str, #[case] expected: usize) {
+        let error = process(input).unwrap_err();
+        assert!(
+            matches!(error, AocError::MalformedInput { line, .. } if line == expected),
+            "{error:?}"
+        );
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(1_000)]
+    #[case(5_000)]
+    fn test_matches_part1(#[case] rows: usize) -> miette::Result<()> {
+        let input = generate_lists(rows, rows as u64);
+        assert_eq!(part1::process(&input)?, process(&input)?);
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "takes seconds in a debug build, run with `cargo test --release -- --ignored`"]
+    fn test_matches_part1_large() -> miette::Result<()> {
+        let input = generate_lists(2_000_000, 2_000_000);
+        assert_eq!(part1::process(&input)?, process(&input)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use aoc_common::scan;
+
+use crate::custom_error::AocError;
+
+/// Column layout of the input, taken from the first line. Every line
+/// of the puzzle input has the same shape (`NNNNN   NNNNN`), so each
+/// number is read at a fixed offset; lines of another shape are
+/// rejected rather than misread.
+struct Layout {
+    left_width: usize,
+    right_offset: usize,
+    len: usize,
+}
+
+fn malformed(line: usize, reason: impl Into<String>) -> AocError {
+    AocError::MalformedInput {
+        line,
+        reason: reason.into(),
+    }
+}
+
+impl Layout {
+    fn detect(line: &[u8]) -> Result<Self, AocError> {
+        let columns = || malformed(1, "is not two columns of numbers");
+        let left_width = line
+            .iter()
+            .position(|&b| b == b' ')
+            .filter(|&width| width > 0)
+            .ok_or_else(columns)?;
+        let right_offset = left_width
+            + line[left_width..]
+                .iter()
+                .position(|&b| b != b' ')
+                .ok_or_else(columns)?;
+        Ok(Self {
+            left_width,
+            right_offset,
+            len: line.len(),
+        })
+    }
+
+    /// The left and right numbers on line `number`.
+    fn numbers(&self, number: usize, line: &[u8]) -> Result<(u32, u32), AocError> {
+        if line.len() != self.len {
+            return Err(malformed(
+                number,
+                format!("is not {} bytes long like the first", self.len),
+            ));
+        }
+        if line[self.left_width..self.right_offset]
+            .iter()
+            .any(|&b| b != b' ')
+        {
+            return Err(malformed(number, "has no spaces between its columns"));
+        }
+        Ok((
+            parse_fixed(&line[..self.left_width], number)?,
+            parse_fixed(&line[self.right_offset..], number)?,
+        ))
+    }
+}
+
+/// [`scan::parse_fixed`] on a column checked to hold only digits, and
+/// few enough of them to fit its word.
+fn parse_fixed(digits: &[u8], line: usize) -> Result<u32, AocError> {
+    if digits.len() > 8 {
+        return Err(malformed(line, "has a number longer than eight digits"));
+    }
+    if let Some(&b) = digits.iter().find(|b| !b.is_ascii_digit()) {
+        return Err(malformed(
+            line,
+            format!("has {:?} where a digit should be", char::from(b)),
+        ));
+    }
+    Ok(scan::parse_fixed(digits) as u32)
+}
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String, AocError> {
+    let mut lines = scan::lines(input).peekable();
+    let Some(first) = lines.peek() else {
+        return Ok(0.to_string());
+    };
+    let layout = Layout::detect(first.as_bytes())?;
+    let mut left = vec![];
+    let mut right = vec![];
+
+    for (idx, line) in lines.enumerate() {
+        let (l, r) = layout.numbers(idx + 1, line.as_bytes())?;
+        left.push(l);
+        right.push(r);
+    }
+
+    left.sort();
+    right.sort();
+
+    let result: u32 = std::iter::zip(left, right)
+        .map(|(l, r)| l.abs_diff(r))
+        .sum();
+
+    Ok(result.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn test_process() -> miette::Result<()> {
+        let input = include_str!("../example1.txt");
+        assert_eq!("11", process(input)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("80414   72092\n17250   2641", 2)]
+    #[case("80414   72092\n17250   264145", 2)]
+    #[case("80414   72092\n17250 9 26414", 2)]
+    #[case("80414   72092\n17250   26a14", 2)]
+    #[case("80414   72092\n\n17250   26414", 2)]
+    #[case("80414   123456789", 1)]
+    #[case("8041472092", 1)]
+    #[case(" 8041472092", 1)]
+    fn test_malformed(#[case] input: &str, #[case] expected: usize) {
+        let error = process(input).unwrap_err();
+        assert!(
+            matches!(error, AocError::MalformedInput { line, .. } if line == expected),
+            "{error:?}"
+        );
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(1_000)]
+    #[case(5_000)]
+    fn test_matches_scalar(#[case] rows: usize) -> miette::Result<()> {
+        let mut rng = fastrand::Rng::with_seed(rows as u64);
+        let input = (0..rows)
+            .map(|_| format!("{}   {}", rng.u32(10000..=99999), rng.u32(10000..=99999)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(part1::process(&input)?, process(&input)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use criterion::{
+    criterion_group, criterion_main, Criterion, Throughput,
+};
+use aoc_common::scan;
+
+/// A list shaped like 2024 day 1: two five digit columns.
+fn input() -> String {
+    let mut rng = fastrand::Rng::with_seed(1);
+    (0..100_000)
+        .map(|_| {
+            format!(
+                "{}   {}\n",
+                rng.u32(10000..=99999),
+                rng.u32(10000..=99999)
+            )
+        })
+        .collect()
+}
+
+fn criterion_benchmark_lines(c: &mut Criterion) {
+    let input = input();
+
+    let mut group = c.benchmark_group("scan::lines");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_with_input("std", input.as_str(), |b, input| {
+        b.iter(|| input.lines().count())
+    });
+    group.bench_with_input("scan", input.as_str(), |b, input| {
+        b.iter(|| scan::lines(input).count())
+    });
+
+    group.finish();
+}
+
+fn criterion_benchmark_numbers(c: &mut Criterion) {
+    let input = input();
+
+    let mut group = c.benchmark_group("scan::numbers");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_with_input("std", input.as_str(), |b, input| {
+        b.iter(|| {
+            input
+                .lines()
+                .flat_map(str::split_whitespace)
+                .map(|num| num.parse::<u64>().unwrap())
+                .sum::<u64>()
+        })
+    });
+    group.bench_with_input("scan", input.as_str(), |b, input| {
+        b.iter(|| {
+            scan::lines(input)
+                .map(|line| {
+                    let line = line.as_bytes();
+                    scan::parse_fixed(&line[..5])
+                        + scan::parse_fixed(&line[8..])
+                })
+                .sum::<u64>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark_lines,
+    criterion_benchmark_numbers
+);
+criterion_main!(benches);
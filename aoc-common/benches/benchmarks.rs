@@ -0,0 +1,59 @@
+use std::sync::LazyLock;
+
+use aoc_common::scan;
+
+/// A list shaped like 2024 day 1: two five digit columns.
+static INPUT: LazyLock<String> = LazyLock::new(|| {
+    let mut rng = fastrand::Rng::with_seed(1);
+    (0..100_000)
+        .map(|_| {
+            format!(
+                "{}   {}\n",
+                rng.u32(10000..=99999),
+                rng.u32(10000..=99999)
+            )
+        })
+        .collect()
+});
+
+fn main() {
+    // Run registered benchmarks.
+    divan::main();
+}
+
+mod lines {
+    use super::*;
+
+    #[divan::bench]
+    fn std() -> usize {
+        divan::black_box(INPUT.as_str()).lines().count()
+    }
+
+    #[divan::bench]
+    fn scan() -> usize {
+        scan::lines(divan::black_box(INPUT.as_str())).count()
+    }
+}
+
+mod numbers {
+    use super::*;
+
+    #[divan::bench]
+    fn std() -> u64 {
+        divan::black_box(INPUT.as_str())
+            .lines()
+            .flat_map(str::split_whitespace)
+            .map(|num| num.parse::<u64>().unwrap())
+            .sum()
+    }
+
+    #[divan::bench]
+    fn scan() -> u64 {
+        scan::lines(divan::black_box(INPUT.as_str()))
+            .map(|line| {
+                let line = line.as_bytes();
+                scan::parse_fixed(&line[..5]) + scan::parse_fixed(&line[8..])
+            })
+            .sum()
+    }
+}
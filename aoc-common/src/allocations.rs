@@ -0,0 +1,243 @@
+//! Counts heap allocations, so a test can hold a variant to an allocation
+//! budget instead of trusting that its parser doesn't allocate.
+//!
+//! [`solutions!`](crate::solutions) installs [`CountingAllocator`] as the
+//! global allocator of every day's tests, so they can simply call
+//! [`assert_within`]:
+//!
+//! ```ignore
+//! // The answer's `String` is the only allocation, however long the input.
+//! aoc_common::allocations::assert_within(1, || process(&input));
+//! ```
+//!
+//! Only allocations on the calling thread count, so tests running in
+//! parallel don't see each other's, and neither do rayon's workers.
+//!
+//! It also keeps the peak of the heap in use across the whole process, so
+//! [`measure_peak`] can tell how much memory a variant needed at once. The
+//! `verify` bins install it to report that for every run.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+/// Wraps the system allocator and counts every allocation per thread.
+pub struct CountingAllocator;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Bytes in use on the heap of the process, and the most since
+/// [`measure_peak`] last reset it.
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static COUNT: Cell<u64> = const { Cell::new(0) };
+    static BYTES: Cell<u64> = const { Cell::new(0) };
+}
+
+fn count(bytes: usize) {
+    // Allocations while the thread shuts down aren't worth counting.
+    let _ = COUNT.try_with(|count| count.set(count.get() + 1));
+    let _ = BYTES.try_with(|total| total.set(total.get() + bytes as u64));
+}
+
+fn grow(bytes: usize) {
+    let live = LIVE.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn shrink(bytes: usize) {
+    LIVE.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+// SAFETY: every call is forwarded to `System` unchanged.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        count(layout.size());
+        grow(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        count(layout.size());
+        grow(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        if new_size > layout.size() {
+            grow(new_size - layout.size());
+        } else {
+            shrink(layout.size() - new_size);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        shrink(layout.size());
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Allocations made while running something; a reallocation counts as
+/// one, with its new size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Allocations {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+impl fmt::Display for Allocations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} allocations of {} bytes in total", self.count, self.bytes)
+    }
+}
+
+fn snapshot() -> Allocations {
+    Allocations {
+        count: COUNT.with(Cell::get),
+        bytes: BYTES.with(Cell::get),
+    }
+}
+
+/// Runs `f` and returns what it allocated on this thread.
+///
+/// # Panics
+///
+/// If [`CountingAllocator`] isn't the global allocator, since nothing
+/// would be counted.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Allocations) {
+    let before = snapshot();
+    let result = f();
+    let after = snapshot();
+    assert!(
+        installed(),
+        "allocations aren't counted: install aoc_common::allocations::CountingAllocator as the #[global_allocator]"
+    );
+    (
+        result,
+        Allocations {
+            count: after.count - before.count,
+            bytes: after.bytes - before.bytes,
+        },
+    )
+}
+
+/// Whether [`CountingAllocator`] is the global allocator.
+pub fn installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f` and returns the most heap it had in use at once, in bytes on
+/// top of what was in use before. Allocations of every thread count, so
+/// rayon's workers do, but so would those of anything else running.
+///
+/// # Panics
+///
+/// If [`CountingAllocator`] isn't the global allocator.
+pub fn measure_peak<R>(f: impl FnOnce() -> R) -> (R, u64) {
+    let before = LIVE.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let result = f();
+    let peak = PEAK.load(Ordering::Relaxed);
+    assert!(
+        installed(),
+        "allocations aren't counted: install aoc_common::allocations::CountingAllocator as the #[global_allocator]"
+    );
+    (result, peak.saturating_sub(before) as u64)
+}
+
+/// Formats a size in bytes the way divan does, to four significant
+/// digits, e.g. `3.751 KB`.
+pub fn format_bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for next in ["KB", "MB", "GB", "TB"] {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = next;
+    }
+    let decimals = 3usize.saturating_sub(value.log10().max(0.0) as usize);
+    let value = format!("{value:.decimals$}");
+    let value = if value.contains('.') {
+        value.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &value
+    };
+    format!("{value} {unit}")
+}
+
+/// Runs `f` and panics if it allocated more than `budget` times.
+pub fn assert_within<R>(budget: u64, f: impl FnOnce() -> R) -> R {
+    let (result, allocations) = measure(f);
+    assert!(
+        allocations.count <= budget,
+        "{allocations}, over the budget of {budget} allocations"
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure() {
+        let (sum, allocations) = measure(|| (1..=10u64).sum::<u64>());
+        assert_eq!(55, sum);
+        assert_eq!(Allocations::default(), allocations);
+
+        let (_, allocations) = measure(|| {
+            let mut numbers = Vec::<u64>::with_capacity(4);
+            numbers.extend([1, 2, 3, 4, 5]);
+            numbers
+        });
+        assert_eq!(2, allocations.count);
+        assert!(allocations.bytes >= 5 * 8, "{allocations}");
+    }
+
+    #[test]
+    fn test_measure_peak() {
+        let (length, peak) = measure_peak(|| {
+            let big = vec![1u8; 8 << 20];
+            big.len()
+        });
+        assert_eq!(8 << 20, length);
+        // Other tests allocate at the same time.
+        assert!(peak >= 4 << 20, "{peak}");
+    }
+
+    #[rstest]
+    #[case(512, "512 B")]
+    #[case(3751, "3.751 KB")]
+    #[case(98_300, "98.3 KB")]
+    #[case(2_000_000, "2 MB")]
+    fn test_format_bytes(#[case] bytes: u64, #[case] expected: &str) {
+        assert_eq!(expected, format_bytes(bytes));
+    }
+
+    #[test]
+    fn test_within_budget() {
+        assert_eq!("42", assert_within(1, || 42.to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "over the budget of 1 allocations")]
+    fn test_over_budget() {
+        assert_within(1, || "a b c".split(' ').map(String::from).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,231 @@
+//! The answers accepted for a year's own inputs, kept in `answers.txt` at
+//! the root of the year's workspace as `day part answer` lines, so that a
+//! refactor can't break an accepted answer unnoticed.
+//!
+//! [`answer_tests!`](crate::answer_tests), declared for every variant by
+//! [`solutions!`](crate::solutions), runs each variant on the day's real
+//! input and compares. `aoc-tool submit` adds answers the site accepts.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{input, runner::Solution, AocError};
+
+pub const MANIFEST: &str = "answers.txt";
+
+const HEADER: &str = "# Accepted answers for this year's inputs: day part answer\n";
+
+#[derive(Debug)]
+pub struct Manifest {
+    path: PathBuf,
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+impl Manifest {
+    /// Loads the manifest of the year in `year_dir`; a missing file is an
+    /// empty manifest.
+    pub fn load(year_dir: &Path) -> Result<Self, AocError> {
+        let path = year_dir.join(MANIFEST);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let answers = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(idx, line)| {
+                parse_entry(line).ok_or_else(|| AocError::InvalidAnswers {
+                    path: path.display().to_string(),
+                    line: idx + 1,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { path, answers })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Answers recorded for every part of `day`.
+    pub fn day(&self, day: u8) -> impl Iterator<Item = (u8, &str)> {
+        self.answers
+            .range((day, 0)..=(day, u8::MAX))
+            .map(|((_, part), answer)| (*part, answer.as_str()))
+    }
+
+    /// Records an accepted answer and rewrites the file in day order.
+    pub fn record(&mut self, day: u8, part: u8, answer: &str) -> Result<(), AocError> {
+        self.answers.insert((day, part), answer.to_string());
+        let mut contents = HEADER.to_string();
+        for ((day, part), answer) in &self.answers {
+            contents.push_str(&format!("{day} {part} {answer}\n"));
+        }
+        fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+fn parse_entry(line: &str) -> Option<((u8, u8), String)> {
+    let mut fields = line.split_whitespace();
+    let day = fields.next()?.parse().ok()?;
+    let part = fields.next()?.parse().ok()?;
+    let answer = fields.next()?.to_string();
+    fields.next().is_none().then_some(((day, part), answer))
+}
+
+/// Day number of a `day-N` directory.
+pub fn day_of(day_dir: &Path) -> Option<u8> {
+    day_dir
+        .file_name()?
+        .to_str()?
+        .strip_prefix("day-")?
+        .parse()
+        .ok()
+}
+
+/// Runs `process` on the real input of `part` in `day_dir` and checks it
+/// returns the answer recorded for the day in its year's manifest. Passes,
+/// with a note on stderr, when there is no recorded answer or no input yet.
+pub fn check_answer(day_dir: &Path, part: u8, process: Solution) -> Result<(), AocError> {
+    let day = day_of(day_dir).ok_or_else(|| AocError::InvalidAnswers {
+        path: day_dir.display().to_string(),
+        line: 0,
+    })?;
+    let year_dir = day_dir.parent().unwrap_or(day_dir);
+    let manifest = Manifest::load(year_dir)?;
+    let Some(expected) = manifest.get(day, part) else {
+        eprintln!("no answer recorded for day {day} part {part}, skipping");
+        return Ok(());
+    };
+    let Some(input) = input::read_or_skip(day_dir, part) else {
+        return Ok(());
+    };
+
+    assert_eq!(
+        expected,
+        process(&input)?,
+        "day {day} part {part} on the real input, recorded in {MANIFEST}"
+    );
+    Ok(())
+}
+
+/// Declares a test per variant that checks it against the answer recorded
+/// for its part. Days get these through [`solutions!`](crate::solutions).
+/// Variants marked `[slow]` get an ignored test, run with `--ignored`.
+#[macro_export]
+macro_rules! answer_tests {
+    ($($(#[$attr:meta])* $name:ident: $part:literal => $process:path $([$flag:ident])?),* $(,)?) => {
+        #[cfg(test)]
+        mod answer_tests {
+            $(
+                $crate::__answer_test! {
+                    $(#[$attr])* $name: $part => $process $([$flag])?
+                }
+            )*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __answer_test {
+    ($(#[$attr:meta])* $name:ident: $part:literal => $process:path [slow]) => {
+        $crate::__answer_test! {
+            $(#[$attr])*
+            #[ignore = "slow on the real input, run with `cargo test --release -- --ignored`"]
+            $name: $part => $process
+        }
+    };
+    ($(#[$attr:meta])* $name:ident: $part:literal => $process:path) => {
+        $(#[$attr])*
+        #[test]
+        fn $name() -> Result<(), $crate::AocError> {
+            $crate::answers::check_answer(
+                ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+                $part,
+                $process,
+            )
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn answer_42(_input: &str) -> Result<String, AocError> {
+        Ok("42".to_string())
+    }
+
+    fn year_with_day() -> (tempfile::TempDir, PathBuf) {
+        let year = tempfile::tempdir().unwrap();
+        let day = year.path().join("day-7");
+        fs::create_dir_all(&day).unwrap();
+        fs::write(day.join("input.txt"), "input").unwrap();
+        (year, day)
+    }
+
+    #[test]
+    fn test_record_and_reload() -> miette::Result<()> {
+        let year = tempfile::tempdir().unwrap();
+        let mut manifest = Manifest::load(year.path())?;
+        manifest.record(12, 1, "4,6,3")?;
+        manifest.record(3, 2, "x")?;
+        manifest.record(3, 1, "7")?;
+
+        assert_eq!(
+            format!("{HEADER}3 1 7\n3 2 x\n12 1 4,6,3\n"),
+            fs::read_to_string(year.path().join(MANIFEST)).unwrap()
+        );
+        let reloaded = Manifest::load(year.path())?;
+        assert_eq!(Some("4,6,3"), reloaded.get(12, 1));
+        assert_eq!(None, reloaded.get(12, 2));
+        assert_eq!(
+            vec![(1, "7"), (2, "x")],
+            reloaded.day(3).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_entry() {
+        let year = tempfile::tempdir().unwrap();
+        fs::write(year.path().join(MANIFEST), "# comment\n1 1 5\n1 two 5\n").unwrap();
+        assert!(matches!(
+            Manifest::load(year.path()),
+            Err(AocError::InvalidAnswers { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_answer() -> miette::Result<()> {
+        let (year, day) = year_with_day();
+        fs::write(year.path().join(MANIFEST), "7 1 42\n").unwrap();
+        check_answer(&day, 1, answer_42)?;
+        // Nothing recorded for part two yet.
+        check_answer(&day, 2, answer_42)?;
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "day 7 part 1 on the real input")]
+    fn test_check_answer_catches_regression() {
+        let (year, day) = year_with_day();
+        fs::write(year.path().join(MANIFEST), "7 1 41\n").unwrap();
+        let _ = check_answer(&day, 1, answer_42);
+    }
+
+    #[test]
+    fn test_day_of() {
+        assert_eq!(Some(25), day_of(Path::new("2023/day-25")));
+        assert_eq!(None, day_of(Path::new("2023/daily-template")));
+    }
+}
@@ -0,0 +1,350 @@
+//! Benchmark harnesses generated from a day's [`SOLUTIONS`], so that every
+//! registered variant is benchmarked by both divan and criterion without
+//! listing it again. A day's `benches/benchmarks.rs` is just
+//!
+//! ```ignore
+//! aoc_common::divan_benches!(day_1);
+//! ```
+//!
+//! and `benches/benchmarks-criterion.rs` the same with
+//! [`criterion_benches!`](crate::criterion_benches). Variants marked
+//! `[slow]` in the registry are left out.
+//!
+//! Besides the real input, every variant also runs on inputs generated at
+//! each of [`SCALES`] times its size, to show how it grows with the input:
+//! a quadratic loop that is fast enough on the real input stands out as a
+//! curve. Inputs are scaled by the day's `SCALE_INPUT`, [`repeat_lines`]
+//! unless it names another [`Scaler`] in its registry.
+//!
+//! Variants that name their parse and solve steps in the registry are also
+//! benchmarked a [`Stage`] at a time, the parse step, the solve step on an
+//! already parsed model and the full pipeline side by side, as
+//! `<variant>/parse`, `<variant>/solve` and `<variant>/full`. The rest
+//! stream through their input without a model to time on its own.
+//!
+//! [`SOLUTIONS`]: crate::solutions
+
+use std::{fmt, path::Path};
+
+use crate::{
+    input,
+    solutions::{Solver, Variant},
+    AocError,
+};
+
+/// Generates an input `factor` times the size of the real one, still valid
+/// for the day's puzzle.
+pub type Scaler = fn(&str, usize) -> String;
+
+/// Multiples of the real input every variant is benchmarked on.
+pub const SCALES: &[usize] = &[1, 10, 100];
+
+/// The input repeated `factor` times, for puzzles whose lines are
+/// independent or whose grid can grow downwards.
+pub fn repeat_lines(input: &str, factor: usize) -> String {
+    vec![input; factor].join("\n")
+}
+
+/// Every line repeated `factor` times over, for puzzles that scan within a
+/// line.
+pub fn widen_lines(input: &str, factor: usize) -> String {
+    input
+        .lines()
+        .map(|line| line.repeat(factor))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The variants worth benchmarking.
+pub fn benched(solutions: &'static [Variant]) -> impl Iterator<Item = &'static Variant> {
+    solutions.iter().filter(|variant| !variant.slow)
+}
+
+/// Part of a variant benchmarked on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// Building the model from the input.
+    Parse,
+    /// Solving an already parsed model.
+    Solve,
+    /// Both, as `process` runs them.
+    Full,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stage::Parse => "parse",
+            Stage::Solve => "solve",
+            Stage::Full => "full",
+        })
+    }
+}
+
+/// A stage of a variant, shown as e.g. `part2_nom/parse`.
+#[derive(Clone, Copy, Debug)]
+pub struct Staged {
+    pub variant: &'static Variant,
+    pub stage: Stage,
+}
+
+impl fmt::Display for Staged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.variant, self.stage)
+    }
+}
+
+impl Staged {
+    /// Calls `bench` with the stage to time on `input`. The solve stage
+    /// parses the input first, outside of whatever `bench` times.
+    pub fn run(self, input: &str, bench: &mut dyn FnMut(&Solver)) -> Result<(), AocError> {
+        let Some(stages) = self.variant.stages else {
+            bench(&|| (self.variant.process)(input));
+            return Ok(());
+        };
+        match self.stage {
+            Stage::Parse => bench(&|| (stages.parse)(input).map(|()| String::new())),
+            Stage::Solve => (stages.with_solver)(input, bench)?,
+            Stage::Full => bench(&|| (self.variant.process)(input)),
+        }
+        Ok(())
+    }
+}
+
+/// Every stage of the benchmarked variants that name theirs.
+pub fn staged(solutions: &'static [Variant]) -> impl Iterator<Item = Staged> {
+    benched(solutions)
+        .filter(|variant| variant.stages.is_some())
+        .flat_map(|variant| {
+            [Stage::Parse, Stage::Solve, Stage::Full]
+                .map(|stage| Staged { variant, stage })
+        })
+}
+
+/// Reads the input of `part` for a benchmark of the crate in
+/// `manifest_dir`, panicking if it can't.
+pub fn input(manifest_dir: &str, part: u8) -> String {
+    input::read(Path::new(manifest_dir), part)
+        .unwrap_or_else(|error| panic!("reading the part {part} input: {error}"))
+}
+
+/// Declares a divan benchmark per variant of the given day crate's
+/// `SOLUTIONS`, and the `main` that runs them. Each shows up as
+/// `solutions::<variant>`, and on scaled inputs as
+/// `scaling::<factor>::<variant>` with its throughput, and a step at a
+/// time as `stages::<variant>/<stage>`, so `just bench day-1 part1` still
+/// picks them by name. Divan's allocation
+/// profiler reports how often and how much each one allocates.
+#[macro_export]
+macro_rules! divan_benches {
+    ($day:ident) => {
+        #[global_allocator]
+        static ALLOCATOR: ::divan::AllocProfiler = ::divan::AllocProfiler::system();
+
+        fn main() {
+            ::divan::main();
+        }
+
+        #[::divan::bench(args = $crate::bench_harness::benched($day::SOLUTIONS))]
+        fn solutions(bencher: ::divan::Bencher, variant: &$crate::solutions::Variant) {
+            let input = $crate::bench_harness::input(env!("CARGO_MANIFEST_DIR"), variant.part);
+            bencher.bench(|| (variant.process)(::divan::black_box(&input)).unwrap());
+        }
+
+        #[::divan::bench(
+            consts = $crate::bench_harness::SCALES,
+            args = $crate::bench_harness::benched($day::SOLUTIONS),
+            sample_count = 10,
+        )]
+        fn scaling<const FACTOR: usize>(bencher: ::divan::Bencher, variant: &$crate::solutions::Variant) {
+            let input = $day::SCALE_INPUT(
+                &$crate::bench_harness::input(env!("CARGO_MANIFEST_DIR"), variant.part),
+                FACTOR,
+            );
+            bencher
+                .counter(::divan::counter::BytesCount::of_str(&input))
+                .bench(|| (variant.process)(::divan::black_box(&input)).unwrap());
+        }
+
+        #[::divan::bench(args = $crate::bench_harness::staged($day::SOLUTIONS))]
+        fn stages(bencher: ::divan::Bencher, staged: $crate::bench_harness::Staged) {
+            let input = $crate::bench_harness::input(env!("CARGO_MANIFEST_DIR"), staged.variant.part);
+            let mut bencher = Some(bencher);
+            staged
+                .run(::divan::black_box(&input), &mut |run| {
+                    if let Some(bencher) = bencher.take() {
+                        bencher.bench(|| run().unwrap());
+                    }
+                })
+                .unwrap();
+        }
+    };
+}
+
+/// Declares a criterion group per part, `day_1::part1` and so on, holding
+/// every variant of the given day crate's `SOLUTIONS`, another per part,
+/// `day_1::part1::scaling`, running them on scaled inputs with their
+/// throughput, one more, `day_1::part1::stages`, timing the steps of those
+/// that name them side by side, and the `main` that runs them.
+#[macro_export]
+macro_rules! criterion_benches {
+    ($day:ident) => {
+        fn solutions(c: &mut ::criterion::Criterion) {
+            for part in [1, 2] {
+                let mut variants = $crate::bench_harness::benched($day::SOLUTIONS)
+                    .filter(|variant| variant.part == part)
+                    .peekable();
+                if variants.peek().is_none() {
+                    continue;
+                }
+                let input = $crate::bench_harness::input(env!("CARGO_MANIFEST_DIR"), part);
+                let mut group = c.benchmark_group(format!("{}::part{part}", stringify!($day)));
+                for variant in variants {
+                    group.bench_with_input(variant.name, &input, |b, input| {
+                        b.iter(|| (variant.process)(input))
+                    });
+                }
+                group.finish();
+            }
+        }
+
+        fn scaling(c: &mut ::criterion::Criterion) {
+            for part in [1, 2] {
+                let variants = $crate::bench_harness::benched($day::SOLUTIONS)
+                    .filter(|variant| variant.part == part)
+                    .collect::<Vec<_>>();
+                if variants.is_empty() {
+                    continue;
+                }
+                let real = $crate::bench_harness::input(env!("CARGO_MANIFEST_DIR"), part);
+                let mut group =
+                    c.benchmark_group(format!("{}::part{part}::scaling", stringify!($day)));
+                group.sample_size(10);
+                for &factor in $crate::bench_harness::SCALES {
+                    let input = $day::SCALE_INPUT(&real, factor);
+                    group.throughput(::criterion::Throughput::Bytes(input.len() as u64));
+                    for variant in &variants {
+                        group.bench_with_input(
+                            ::criterion::BenchmarkId::new(variant.name, format!("{factor}x")),
+                            &input,
+                            |b, input| b.iter(|| (variant.process)(input)),
+                        );
+                    }
+                }
+                group.finish();
+            }
+        }
+
+        fn stages(c: &mut ::criterion::Criterion) {
+            for part in [1, 2] {
+                let staged = $crate::bench_harness::staged($day::SOLUTIONS)
+                    .filter(|staged| staged.variant.part == part)
+                    .collect::<Vec<_>>();
+                if staged.is_empty() {
+                    continue;
+                }
+                let input = $crate::bench_harness::input(env!("CARGO_MANIFEST_DIR"), part);
+                let mut group =
+                    c.benchmark_group(format!("{}::part{part}::stages", stringify!($day)));
+                for staged in staged {
+                    group.bench_function(
+                        ::criterion::BenchmarkId::new(staged.variant.name, staged.stage),
+                        |b| {
+                            staged
+                                .run(&input, &mut |run| b.iter(run))
+                                .unwrap()
+                        },
+                    );
+                }
+                group.finish();
+            }
+        }
+
+        ::criterion::criterion_group!(benches, solutions, scaling, stages);
+        ::criterion::criterion_main!(benches);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AocError;
+    use pretty_assertions::assert_eq;
+
+    fn noop(_input: &str) -> Result<String, AocError> {
+        Ok(String::new())
+    }
+
+    static SOLUTIONS: &[Variant] = &[
+        Variant {
+            part: 1,
+            name: "part1",
+            process: noop,
+            slow: false,
+            stages: None,
+        },
+        Variant {
+            part: 2,
+            name: "part2_brute_force",
+            process: noop,
+            slow: true,
+            stages: None,
+        },
+    ];
+
+    #[test]
+    fn test_scalers() {
+        assert_eq!("ab\nc\nab\nc", repeat_lines("ab\nc", 2));
+        assert_eq!("abab\ncc", widen_lines("ab\nc", 2));
+        assert_eq!("ab\nc", widen_lines("ab\nc", 1));
+    }
+
+    #[test]
+    fn test_staged() -> miette::Result<()> {
+        fn count(input: &str) -> Result<String, AocError> {
+            solve(&parse(input)?)
+        }
+        fn parse(input: &str) -> Result<Vec<&str>, AocError> {
+            Ok(input.split_whitespace().collect())
+        }
+        fn solve(words: &[&str]) -> Result<String, AocError> {
+            Ok(words.len().to_string())
+        }
+        static SOLUTIONS: &[Variant] = &[
+            Variant {
+                part: 1,
+                name: "part1",
+                process: noop,
+                slow: false,
+                stages: None,
+            },
+            Variant {
+                part: 1,
+                name: "part1_words",
+                process: count,
+                slow: false,
+                stages: crate::__stages!({ parse: parse, solve: solve }),
+            },
+        ];
+
+        let staged = staged(SOLUTIONS).collect::<Vec<_>>();
+        assert_eq!(
+            vec!["part1_words/parse", "part1_words/solve", "part1_words/full"],
+            staged.iter().map(ToString::to_string).collect::<Vec<_>>()
+        );
+        let mut answers = vec![];
+        for staged in staged {
+            staged.run("a b c", &mut |run| answers.push(run().unwrap()))?;
+        }
+        assert_eq!(vec!["", "3", "3"], answers);
+        Ok(())
+    }
+
+    #[test]
+    fn test_slow_variants_are_not_benched() {
+        assert_eq!(
+            vec!["part1"],
+            benched(SOLUTIONS).map(ToString::to_string).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,651 @@
+//! History of benchmark results, to notice when a change made a solution
+//! slower.
+//!
+//! `just bench` pipes divan's tables through `aoc-tool bench record`, which
+//! [parses](parse_divan) them and appends one line per benchmark to the
+//! year's `bench-history.tsv`: `unix\trev\tbench\tfastest\tslowest\tmedian\t
+//! mean\tsamples\titers\tpeak`, times in nanoseconds and the peak heap in
+//! bytes, `-` if divan's allocation profiler didn't report it. Lines
+//! recorded before the peak was kept have no such field. `just bench-compare` then
+//! [compares](History::compare) the latest result of every benchmark with
+//! its baseline, either the runs at a given revision or the few before it.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::AocError;
+
+pub const HISTORY_FILE: &str = "bench-history.tsv";
+
+/// How many earlier runs make the baseline when no revision is given.
+pub const BASELINE_RUNS: usize = 5;
+
+/// Slowdown of the median, as a fraction, below which a change is noise.
+pub const DEFAULT_TOLERANCE: f64 = 0.05;
+
+/// One benchmark's row of a divan table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    /// Path of the benchmark, e.g. `day_1_bench::part1`.
+    pub bench: String,
+    pub fastest: Duration,
+    pub slowest: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub samples: u64,
+    pub iters: u64,
+    /// Most heap in use at once in the median sample, in bytes, from the
+    /// `max alloc` row of divan's allocation profiler.
+    pub peak: Option<u64>,
+}
+
+/// A day's variant, as named by its divan benchmark.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Key {
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+}
+
+impl Key {
+    /// Key of a benchmark on the real input, `day_3_bench::solutions::part2`,
+    /// or `day_3_bench::part2` as recorded before the benches were grouped.
+    pub fn of(bench: &str) -> Option<Self> {
+        let mut segments = bench.split("::");
+        let day = segments
+            .next()?
+            .strip_prefix("day_")?
+            .strip_suffix("_bench")?
+            .parse()
+            .ok()?;
+        let variant = match (segments.next()?, segments.next(), segments.next()) {
+            ("solutions", Some(variant), None) | (variant, None, None) => variant,
+            _ => return None,
+        };
+        let part = variant.strip_prefix("part")?.chars().next()?.to_digit(10)? as u8;
+        Some(Self {
+            day,
+            part,
+            variant: variant.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} {}", self.day, self.variant)
+    }
+}
+
+/// A [`Measurement`] as recorded in the history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    /// Unix time of the recording.
+    pub at: u64,
+    /// Git revision benchmarked, e.g. `47f88f0` or `47f88f0+dirty`.
+    pub rev: String,
+    pub measurement: Measurement,
+}
+
+/// Parses every table divan printed in `output`, ignoring anything else
+/// such as the test harness's `running 0 tests`.
+pub fn parse_divan(output: &str) -> Vec<Measurement> {
+    let mut measurements: Vec<Measurement> = Vec::new();
+    // Names of the enclosing bench binary and groups.
+    let mut path: Vec<String> = Vec::new();
+    // Rows left until the bytes row under a `max alloc:` heading, which
+    // has the counts first.
+    let mut peak_rows = 0;
+    for line in output.lines() {
+        let columns = line.split('│').map(str::trim).collect::<Vec<_>>();
+        if columns.len() < 6 {
+            continue;
+        }
+        if columns[0].ends_with("fastest") {
+            path = columns[0].split_whitespace().take(1).map(String::from).collect();
+            continue;
+        }
+        let Some(name_start) = line.find(|c: char| !"│├╰─ ".contains(c)) else {
+            continue;
+        };
+        if path.is_empty() {
+            continue;
+        }
+        let depth = line[..name_start].chars().count() / 3;
+        let mut segments = line[name_start..].split('│');
+        let first_segment = segments.next().unwrap_or_default().trim_end();
+        let mut first = first_segment.split_whitespace();
+        let Some(name) = first.next() else {
+            continue;
+        };
+        // Lines under a row: throughput such as `75.7 MB/s`, and the
+        // allocation profiler's headings such as `max alloc:` and counts.
+        // Groups named by a number, like the scaling factors, have no
+        // other columns.
+        let numeric = name.parse::<f64>().is_ok()
+            && (first.clone().next().is_some() || segments.any(|segment| !segment.trim().is_empty()));
+        if first_segment.ends_with(':') {
+            peak_rows = if first_segment == "max alloc:" { 2 } else { 0 };
+            continue;
+        }
+        if numeric {
+            if peak_rows > 0 {
+                peak_rows -= 1;
+                if let (0, Some(measurement)) = (peak_rows, measurements.last_mut()) {
+                    measurement.peak = parse_bytes(columns[columns.len() - 4]);
+                }
+            }
+            continue;
+        }
+        peak_rows = 0;
+        path.truncate(depth);
+        path.push(name.to_string());
+
+        let fastest = first.collect::<Vec<_>>().join(" ");
+        let offset = columns.len() - 5;
+        let parsed = (|| {
+            Some(Measurement {
+                bench: path.join("::"),
+                fastest: parse_duration(&fastest)?,
+                slowest: parse_duration(columns[offset])?,
+                median: parse_duration(columns[offset + 1])?,
+                mean: parse_duration(columns[offset + 2])?,
+                samples: columns[offset + 3].parse().ok()?,
+                iters: columns[offset + 4].parse().ok()?,
+                peak: None,
+            })
+        })();
+        // Rows of groups have no numbers of their own.
+        if let Some(measurement) = parsed {
+            measurements.push(measurement);
+        }
+    }
+    measurements
+}
+
+/// Parses a divan time such as `63.41 µs`.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let (value, unit) = text.trim().split_once(' ')?;
+    let nanos_per_unit = match unit {
+        "ps" => 1e-3,
+        "ns" => 1.0,
+        "µs" | "us" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        _ => return None,
+    };
+    let nanos = (value.parse::<f64>().ok()? * nanos_per_unit).round();
+    Some(Duration::from_nanos(nanos as u64))
+}
+
+/// Parses a divan size such as `3.751 KB`.
+fn parse_bytes(text: &str) -> Option<u64> {
+    let (value, unit) = text.trim().split_once(' ')?;
+    let bytes_per_unit = match unit {
+        "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value.parse::<f64>().ok()? * bytes_per_unit).round() as u64)
+}
+
+/// What became of a benchmark's median compared with its baseline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Regressed,
+    Improved,
+    Unchanged,
+    /// Nothing to compare with.
+    New,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Regressed => "REGRESSED",
+            Self::Improved => "improved",
+            Self::Unchanged => "unchanged",
+            Self::New => "new",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub bench: String,
+    /// Median of the baseline runs' medians.
+    pub baseline: Option<Duration>,
+    pub latest: Duration,
+    pub change: Change,
+}
+
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    runs: Vec<Run>,
+}
+
+impl History {
+    /// Loads the history in `path`; a missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self, AocError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let runs = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                parse_run(line).ok_or_else(|| AocError::InvalidBenchHistory {
+                    path: path.display().to_string(),
+                    line: idx + 1,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            runs,
+        })
+    }
+
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    /// Latest median of every variant benchmarked on the real input.
+    pub fn latest_medians(&self) -> BTreeMap<Key, Duration> {
+        self.runs
+            .iter()
+            .filter_map(|run| Some((Key::of(&run.measurement.bench)?, run.measurement.median)))
+            .collect()
+    }
+
+    /// Appends `measurements`, taken at `rev`, to the file.
+    pub fn record(
+        &mut self,
+        at: u64,
+        rev: &str,
+        measurements: &[Measurement],
+    ) -> Result<(), AocError> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        for measurement in measurements {
+            let Measurement {
+                bench,
+                fastest,
+                slowest,
+                median,
+                mean,
+                samples,
+                iters,
+                peak,
+            } = measurement;
+            writeln!(
+                file,
+                "{at}\t{rev}\t{bench}\t{}\t{}\t{}\t{}\t{samples}\t{iters}\t{}",
+                fastest.as_nanos(),
+                slowest.as_nanos(),
+                median.as_nanos(),
+                mean.as_nanos(),
+                peak.map_or_else(|| "-".to_string(), |peak| peak.to_string())
+            )?;
+            self.runs.push(Run {
+                at,
+                rev: rev.to_string(),
+                measurement: measurement.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Compares the latest run of every benchmark with its baseline: the
+    /// runs at `baseline_rev` if given, otherwise the [`BASELINE_RUNS`]
+    /// before it.
+    ///
+    /// A median counts as regressed only if it is slower than the baseline
+    /// by more than `tolerance`, or three standard deviations of the
+    /// baseline medians if they are noisier than that, and even the fastest
+    /// sample was slower than the baseline median.
+    pub fn compare(&self, baseline_rev: Option<&str>, tolerance: f64) -> Vec<Comparison> {
+        let mut benches = self
+            .runs
+            .iter()
+            .map(|run| run.measurement.bench.as_str())
+            .collect::<Vec<_>>();
+        benches.sort();
+        benches.dedup();
+
+        benches
+            .into_iter()
+            .filter_map(|bench| {
+                let runs = self
+                    .runs
+                    .iter()
+                    .filter(|run| run.measurement.bench == bench)
+                    .collect::<Vec<_>>();
+                let (latest, earlier) = runs.split_last()?;
+                let baseline = match baseline_rev {
+                    Some(rev) => earlier.iter().filter(|run| run.rev == rev).copied().collect(),
+                    None => earlier[earlier.len().saturating_sub(BASELINE_RUNS)..].to_vec(),
+                };
+                Some(compare_with(&latest.measurement, &baseline, tolerance))
+            })
+            .collect()
+    }
+}
+
+fn compare_with(latest: &Measurement, baseline: &[&Run], tolerance: f64) -> Comparison {
+    let mut medians = baseline
+        .iter()
+        .map(|run| run.measurement.median.as_secs_f64())
+        .collect::<Vec<_>>();
+    medians.sort_by(f64::total_cmp);
+    let Some(&middle) = medians.get(medians.len() / 2) else {
+        return Comparison {
+            bench: latest.bench.clone(),
+            baseline: None,
+            latest: latest.median,
+            change: Change::New,
+        };
+    };
+
+    let mean = medians.iter().sum::<f64>() / medians.len() as f64;
+    let variance =
+        medians.iter().map(|median| (median - mean).powi(2)).sum::<f64>() / medians.len() as f64;
+    let threshold = tolerance.max(3.0 * variance.sqrt() / middle);
+    let median = latest.median.as_secs_f64();
+    let change = if median > middle * (1.0 + threshold) && latest.fastest.as_secs_f64() > middle {
+        Change::Regressed
+    } else if median < middle * (1.0 - threshold) {
+        Change::Improved
+    } else {
+        Change::Unchanged
+    };
+    Comparison {
+        bench: latest.bench.clone(),
+        baseline: Some(Duration::from_secs_f64(middle)),
+        latest: latest.median,
+        change,
+    }
+}
+
+fn parse_run(line: &str) -> Option<Run> {
+    let fields = line.split('\t').collect::<Vec<_>>();
+    let [at, rev, bench, fastest, slowest, median, mean, samples, iters] = fields[..fields.len().min(9)] else {
+        return None;
+    };
+    let peak = match fields[9..] {
+        [] | ["-"] => None,
+        [peak] => Some(peak.parse().ok()?),
+        _ => return None,
+    };
+    let nanos = |field: &str| field.parse().ok().map(Duration::from_nanos);
+    Some(Run {
+        at: at.parse().ok()?,
+        rev: rev.to_string(),
+        measurement: Measurement {
+            bench: bench.to_string(),
+            fastest: nanos(fastest)?,
+            slowest: nanos(slowest)?,
+            median: nanos(median)?,
+            mean: nanos(mean)?,
+            samples: samples.parse().ok()?,
+            iters: iters.parse().ok()?,
+            peak,
+        },
+    })
+}
+
+/// Formats comparisons as an aligned table.
+pub fn report(comparisons: &[Comparison]) -> String {
+    let width = comparisons
+        .iter()
+        .map(|comparison| comparison.bench.len())
+        .chain(["bench".len()])
+        .max()
+        .unwrap_or_default();
+    let mut table = format!("{:width$}  {:>10}  {:>10}  {:>8}  result\n", "bench", "baseline", "latest", "change");
+    for comparison in comparisons {
+        let (baseline, change) = match comparison.baseline {
+            Some(baseline) => (
+                format!("{baseline:.2?}"),
+                format!(
+                    "{:+.1}%",
+                    (comparison.latest.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0
+                ),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        table.push_str(&format!(
+            "{:width$}  {baseline:>10}  {:>10}  {change:>8}  {}\n",
+            comparison.bench,
+            format!("{:.2?}", comparison.latest),
+            comparison.change
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    const OUTPUT: &str = "\
+Timer precision: 20 ns
+day_1_bench   fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ part1      38.83 µs      │ 2.881 ms      │ 83.95 µs      │ 164.2 µs      │ 100     │ 100
+├─ nested                   │               │               │               │         │
+│  ╰─ inner   900 ps        │ 1.2 ns        │ 1 ns          │ 1 ns          │ 100     │ 12800
+├─ part2_nom  523.9 µs      │ 816.2 µs      │ 534.8 µs      │ 562.3 µs      │ 100     │ 100
+│             max alloc:    │               │               │               │         │
+│               3           │ 3             │ 3             │ 3             │         │
+│               3.751 KB    │ 3.751 KB      │ 3.751 KB      │ 3.751 KB      │         │
+│             alloc:        │               │               │               │         │
+│               1           │ 1             │ 1             │ 1             │         │
+╰─ scaling                  │               │               │               │         │
+   ╰─ 10                    │               │               │               │         │
+      ╰─ part1  744.2 µs    │ 864.2 µs      │ 801.8 µs      │ 798.2 µs      │ 10      │ 10
+                285.1 MB/s  │ 245.5 MB/s    │ 264.6 MB/s    │ 265.8 MB/s    │         │
+
+
+running 0 tests
+
+test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+
+    fn micros(value: f64) -> Duration {
+        Duration::from_secs_f64(value / 1e6)
+    }
+
+    fn measurement(bench: &str, fastest: f64, median: f64) -> Measurement {
+        Measurement {
+            bench: bench.to_string(),
+            fastest: micros(fastest),
+            slowest: micros(median * 2.0),
+            median: micros(median),
+            mean: micros(median),
+            samples: 100,
+            iters: 100,
+            peak: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_divan() {
+        let measurements = parse_divan(OUTPUT);
+        assert_eq!(
+            vec![
+                "day_1_bench::part1",
+                "day_1_bench::nested::inner",
+                "day_1_bench::part2_nom",
+                "day_1_bench::scaling::10::part1"
+            ],
+            measurements.iter().map(|m| m.bench.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Measurement {
+                bench: "day_1_bench::part1".to_string(),
+                fastest: Duration::from_nanos(38_830),
+                slowest: Duration::from_nanos(2_881_000),
+                median: Duration::from_nanos(83_950),
+                mean: Duration::from_nanos(164_200),
+                samples: 100,
+                iters: 100,
+                peak: None,
+            },
+            measurements[0]
+        );
+        assert_eq!(Duration::from_nanos(1), measurements[1].median);
+        assert_eq!(12800, measurements[1].iters);
+        assert_eq!(Some(3751), measurements[2].peak);
+        assert_eq!(None, measurements[3].peak);
+    }
+
+    #[rstest]
+    #[case("day_3_bench::solutions::part2_nom", Some((3, 2, "part2_nom")))]
+    #[case("day_12_bench::part1", Some((12, 1, "part1")))]
+    #[case("day_3_bench::stages::part2_nom/parse", None)]
+    #[case("day_3_bench::scaling::10::part1", None)]
+    #[case("aoc_common_bench::runner", None)]
+    fn test_key(#[case] bench: &str, #[case] expected: Option<(u8, u8, &str)>) {
+        assert_eq!(
+            expected.map(|(day, part, variant)| Key {
+                day,
+                part,
+                variant: variant.to_string()
+            }),
+            Key::of(bench)
+        );
+    }
+
+    #[test]
+    fn test_latest_medians() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = History::load(&dir.path().join(HISTORY_FILE))?;
+        history.record(1, "old", &[measurement("day_1_bench::solutions::part1", 1.0, 50.0)])?;
+        history.record(
+            2,
+            "new",
+            &[
+                measurement("day_1_bench::solutions::part1", 1.0, 40.0),
+                measurement("day_1_bench::stages::part1/parse", 1.0, 10.0),
+            ],
+        )?;
+        let key = Key::of("day_1_bench::solutions::part1").unwrap();
+        assert_eq!(BTreeMap::from([(key, micros(40.0))]), history.latest_medians());
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_and_reload() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+        let mut history = History::load(&path)?;
+        history.record(100, "abc1234", &parse_divan(OUTPUT))?;
+        history.record(200, "def5678+dirty", &[measurement("day_2_bench::part1", 1.0, 2.0)])?;
+
+        let reloaded = History::load(&path)?;
+        assert_eq!(history.runs(), reloaded.runs());
+        assert_eq!(5, reloaded.runs().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_lines_without_peak() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+        fs::write(&path, "1\tabc\tday_1_bench::part1\t1\t2\t1\t1\t100\t100\n").unwrap();
+        let history = History::load(&path)?;
+        assert_eq!(None, history.runs()[0].measurement.peak);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = History::load(&dir.path().join(HISTORY_FILE))?;
+        for (at, median) in [(1, 100.0), (2, 102.0), (3, 98.0)] {
+            history.record(
+                at,
+                "old",
+                &[
+                    measurement("slower", median * 0.9, median),
+                    measurement("faster", median * 0.9, median),
+                    measurement("noise", median * 0.9, median),
+                ],
+            )?;
+        }
+        history.record(
+            4,
+            "new",
+            &[
+                measurement("slower", 110.0, 120.0),
+                measurement("faster", 60.0, 70.0),
+                // Slower median, but the fastest sample is as fast as ever.
+                measurement("noise", 90.0, 120.0),
+                measurement("fresh", 1.0, 1.0),
+            ],
+        )?;
+
+        let changes = history
+            .compare(None, DEFAULT_TOLERANCE)
+            .into_iter()
+            .map(|comparison| (comparison.bench, comparison.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("faster".to_string(), Change::Improved),
+                ("fresh".to_string(), Change::New),
+                ("noise".to_string(), Change::Unchanged),
+                ("slower".to_string(), Change::Regressed),
+            ],
+            changes
+        );
+        assert_eq!(
+            Change::New,
+            history.compare(Some("missing"), DEFAULT_TOLERANCE)[0].change
+        );
+
+        let table = report(&history.compare(Some("old"), DEFAULT_TOLERANCE));
+        assert!(table.contains("slower    100.00µs    120.00µs    +20.0%  REGRESSED"), "{table}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_noisy_baseline_raises_threshold() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = History::load(&dir.path().join(HISTORY_FILE))?;
+        for (at, median) in [(1, 60.0), (2, 100.0), (3, 140.0), (4, 120.0)] {
+            history.record(at, "old", &[measurement("jittery", median, median)])?;
+        }
+        assert_eq!(Change::Unchanged, history.compare(None, DEFAULT_TOLERANCE)[0].change);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+        fs::write(&path, "1\tabc\tday_1_bench::part1\t1\t2\n").unwrap();
+        assert!(matches!(
+            History::load(&path),
+            Err(AocError::InvalidBenchHistory { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use aoc_common::{
+    answers::Manifest,
+    bench_history::{self, Change, History},
+    client::{Client, SessionSources},
+    dashboard::Dashboard,
+    input,
+    integrity::Fingerprints,
+    ledger::{self, Verdict},
+    vault::{self, Key, KeySources},
+    AocError,
+};
+use clap::{Parser, Subcommand};
+use miette::{Context, IntoDiagnostic};
+
+/// Talks to adventofcode.com on behalf of the justfiles.
+#[derive(Debug, Parser)]
+struct Cli {
+    /// File holding the session cookie, tried after
+    /// `~/.adventofcode.session`.
+    #[arg(long, global = true)]
+    session_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Writes a day's `puzzle.md` and `input.txt` into `dir`.
+    Download {
+        #[arg(long)]
+        year: u16,
+        #[arg(long)]
+        day: u8,
+        #[arg(long)]
+        dir: PathBuf,
+    },
+    /// Submits an answer, unless the `submissions.tsv` ledger in `dir`
+    /// shows it can't be right, and records the verdict there. Accepted
+    /// answers are added to the year's `answers.txt`.
+    Submit {
+        #[arg(long)]
+        year: u16,
+        #[arg(long)]
+        day: u8,
+        #[arg(long)]
+        dir: PathBuf,
+        part: u8,
+        answer: String,
+    },
+    /// Manages the key the committed `*.enc` inputs are sealed with.
+    #[command(subcommand)]
+    Vault(VaultCommand),
+    /// Records the fingerprint of every input of the given day
+    /// directories, e.g. after replacing one on purpose.
+    Fingerprint { dirs: Vec<PathBuf> },
+    /// Keeps the history of benchmark results and charts it.
+    #[command(subcommand)]
+    Bench(BenchCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum BenchCommand {
+    /// Passes divan's output on stdin through and appends the results to
+    /// `history`, tagged with the current time and git revision.
+    Record { history: PathBuf },
+    /// Compares the latest result of every benchmark in `history` with its
+    /// baseline and fails if any regressed.
+    Compare {
+        history: PathBuf,
+        /// Revision to compare with, rather than the runs before the latest.
+        #[arg(long)]
+        baseline: Option<String>,
+        /// Slowdown of the median, in percent, still counted as noise.
+        #[arg(long, default_value_t = bench_history::DEFAULT_TOLERANCE * 100.0)]
+        tolerance: f64,
+    },
+    /// Renders the history of every year among `dirs` as one HTML page.
+    Report {
+        dirs: Vec<PathBuf>,
+        #[arg(long, default_value = "bench-report.html")]
+        out: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum VaultCommand {
+    /// Creates a key in the config directory, unless one exists.
+    Keygen,
+    /// Seals every `input*.txt` of the given day directories to
+    /// `input*.txt.enc`.
+    Seal { dirs: Vec<PathBuf> },
+    /// Decrypts every `input*.txt.enc` of the given day directories to a
+    /// plain, git-ignored copy.
+    Open { dirs: Vec<PathBuf> },
+}
+
+/// The directories among `paths`, which the justfiles pass as `day-*` and
+/// so also match files like `day-1.bench.txt`.
+fn day_dirs(paths: Vec<PathBuf>) -> impl Iterator<Item = PathBuf> {
+    paths.into_iter().filter(|path| path.is_dir())
+}
+
+/// Names of the inputs in `dir` with the given suffix, e.g. `.txt.enc`.
+fn inputs(dir: &Path, suffix: &str) -> miette::Result<Vec<String>> {
+    let mut names = fs::read_dir(dir)
+        .into_diagnostic()
+        .with_context(|| format!("list {}", dir.display()))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.starts_with("input") && name.ends_with(suffix))
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names)
+}
+
+fn vault(command: VaultCommand) -> miette::Result<()> {
+    match command {
+        VaultCommand::Keygen => {
+            let sources = KeySources::from_env();
+            if sources.find().is_ok() {
+                println!("a key already exists, keeping it");
+                return Ok(());
+            }
+            let path = sources
+                .default_file()
+                .ok_or_else(|| miette::miette!("no config directory to keep the key in"))?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(AocError::from)?;
+            }
+            fs::write(&path, Key::generate().to_hex()).map_err(AocError::from)?;
+            println!("wrote a new key to {}; keep a copy somewhere safe", path.display());
+        }
+        VaultCommand::Seal { dirs } => {
+            let key = Key::find()?;
+            for dir in day_dirs(dirs) {
+                for name in inputs(&dir, ".txt")? {
+                    let path = vault::seal_file(&key, &dir, &name)?;
+                    println!("sealed {}", path.display());
+                }
+            }
+        }
+        VaultCommand::Open { dirs } => {
+            for dir in day_dirs(dirs) {
+                for sealed in inputs(&dir, ".txt.enc")? {
+                    let name = sealed.trim_end_matches(".enc");
+                    let input = vault::read_input(&dir, name)?;
+                    fs::write(dir.join(name), input).map_err(AocError::from)?;
+                    println!("opened {}", dir.join(name).display());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn fingerprint(dirs: Vec<PathBuf>) -> miette::Result<()> {
+    for dir in day_dirs(dirs) {
+        let mut names = inputs(&dir, ".txt")?;
+        names.extend(
+            inputs(&dir, ".txt.enc")?
+                .iter()
+                .map(|sealed| sealed.trim_end_matches(".enc").to_string()),
+        );
+        names.sort();
+        names.dedup();
+        let mut fingerprints = Fingerprints::load(&dir)?;
+        for name in names {
+            let input = input::normalize(&vault::read_input(&dir, &name)?);
+            fingerprints.record(&name, &input)?;
+            println!("fingerprinted {}", dir.join(name).display());
+        }
+    }
+    Ok(())
+}
+
+/// Short revision checked out where `path` lives, marked `+dirty` if
+/// there are uncommitted changes.
+fn git_revision(path: &Path) -> String {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let git = |args: &[&str]| {
+        let output = process::Command::new("git").args(args).current_dir(dir).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    match (git(&["rev-parse", "--short", "HEAD"]), git(&["status", "--porcelain"])) {
+        (Some(rev), Some(status)) if !status.is_empty() => format!("{rev}+dirty"),
+        (Some(rev), _) => rev,
+        (None, _) => "unknown".to_string(),
+    }
+}
+
+fn bench(command: BenchCommand) -> miette::Result<()> {
+    match command {
+        BenchCommand::Record { history } => {
+            let mut output = String::new();
+            for line in io::stdin().lines() {
+                let line = line.map_err(AocError::from)?;
+                println!("{line}");
+                output.push_str(&line);
+                output.push('\n');
+            }
+            let measurements = bench_history::parse_divan(&output);
+            History::load(&history)?.record(ledger::now(), &git_revision(&history), &measurements)?;
+            eprintln!("recorded {} results in {}", measurements.len(), history.display());
+        }
+        BenchCommand::Compare {
+            history,
+            baseline,
+            tolerance,
+        } => {
+            let comparisons = History::load(&history)?.compare(baseline.as_deref(), tolerance / 100.0);
+            print!("{}", bench_history::report(&comparisons));
+            let regressed = comparisons
+                .iter()
+                .filter(|comparison| comparison.change == Change::Regressed)
+                .count();
+            if regressed > 0 {
+                return Err(AocError::BenchRegressed { regressed }.into());
+            }
+        }
+        BenchCommand::Report { dirs, out } => {
+            let html = Dashboard::load(&dirs)?.render()?;
+            fs::write(&out, html)
+                .into_diagnostic()
+                .with_context(|| format!("write {}", out.display()))?;
+            println!("wrote {}", out.display());
+        }
+    }
+    Ok(())
+}
+
+fn main() -> miette::Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Vault(command) => return vault(command),
+        Command::Fingerprint { dirs } => return fingerprint(dirs),
+        Command::Bench(command) => return bench(command),
+        _ => {}
+    }
+    let session = SessionSources::from_env(cli.session_file).find()?;
+    let client = Client::new(session);
+
+    match cli.command {
+        Command::Download { year, day, dir } => {
+            client
+                .download(year, day, &dir)
+                .with_context(|| format!("download {year} day {day}"))?;
+            println!("wrote puzzle.md and input.txt to {}", dir.display());
+            match Key::find() {
+                Ok(key) => {
+                    let path = vault::seal_file(&key, &dir, input::CANONICAL)?;
+                    println!("sealed {}", path.display());
+                }
+                Err(error) => eprintln!("not sealing input.txt: {error}"),
+            }
+        }
+        Command::Submit {
+            year,
+            day,
+            dir,
+            part,
+            answer,
+        } => {
+            let verdict = client
+                .submit_guarded(year, day, part, &answer, &dir)
+                .with_context(|| format!("submit {year} day {day} part {part}"))?;
+            if verdict == Verdict::Correct {
+                let year_dir = dir.parent().unwrap_or(&dir);
+                Manifest::load(year_dir)?.record(day, part, answer.trim())?;
+                // Fingerprint the input the answer is for, unless it was on
+                // download.
+                let name = input::name_for_part(&dir, part);
+                let mut fingerprints = Fingerprints::load(&dir)?;
+                if fingerprints.get(&name).is_none() {
+                    let input = input::normalize(&vault::read_input(&dir, &name)?);
+                    fingerprints.record(&name, &input)?;
+                }
+            }
+            match verdict {
+                Verdict::Correct => println!("{answer} is correct"),
+                Verdict::TooHigh => println!("{answer} is too high"),
+                Verdict::TooLow => println!("{answer} is too low"),
+                Verdict::Wrong => println!("{answer} is wrong"),
+                Verdict::Wait(left) => {
+                    println!("not judged, wait {}s before trying again", left.as_secs())
+                }
+                Verdict::AlreadySolved => println!("part {part} is already solved"),
+            }
+        }
+        Command::Vault(_) | Command::Fingerprint { .. } | Command::Bench(_) => {
+            unreachable!("handled without a session")
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+//! Time budgets per part, so that a solution regressing from microseconds
+//! to seconds fails a test instead of going unnoticed until the next
+//! benchmark, and the year as a whole stays under a second.
+//!
+//! A day declares its budgets in its [`solutions!`](crate::solutions)
+//! registry, which declares a [budget test](crate::budget_tests) per
+//! variant. They run the variant on the real input a few times and fail if
+//! its median is over the budget of its part. Timings of a debug build mean
+//! nothing, so the tests are ignored there; `just budgets` runs them in
+//! release mode, one at a time so they don't slow each other down.
+//!
+//! Unlike the runner's `AOC_TIME_BUDGET`, which cancels a run that takes
+//! too long, these budgets are what a variant is expected to take.
+
+use std::{
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{input, runner::Solution, solutions::Variant, AocError};
+
+/// Most runs a median is taken over.
+pub const SAMPLES: usize = 21;
+
+/// How long to keep sampling for, once there are at least three runs.
+pub const SAMPLING_TIME: Duration = Duration::from_secs(1);
+
+/// Median time a part's variants may take on the real input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartBudget {
+    pub part: u8,
+    pub median: Duration,
+}
+
+/// Median time of `process` on `input` after a warm-up run, and the number
+/// of runs it was taken over.
+pub fn median_time(input: &str, process: Solution) -> Result<(Duration, usize), AocError> {
+    black_box(process(black_box(input))?);
+    let mut times = Vec::with_capacity(SAMPLES);
+    let start = Instant::now();
+    while times.len() < SAMPLES && (times.len() < 3 || start.elapsed() < SAMPLING_TIME) {
+        let run = Instant::now();
+        black_box(process(black_box(input))?);
+        times.push(run.elapsed());
+    }
+    times.sort();
+    Ok((times[times.len() / 2], times.len()))
+}
+
+/// Checks `variant` takes no longer than the budget of its part on the real
+/// input in `day_dir`. Passes, with a note on stderr, for variants marked
+/// `[slow]`, parts without a budget and days without an input yet.
+pub fn check(day_dir: &Path, variant: &Variant, budgets: &[PartBudget]) -> Result<(), AocError> {
+    if variant.slow {
+        eprintln!("{variant} is marked slow, skipping its budget");
+        return Ok(());
+    }
+    let Some(budget) = budgets.iter().find(|budget| budget.part == variant.part) else {
+        eprintln!("no time budget for part {}, skipping", variant.part);
+        return Ok(());
+    };
+    let Some(input) = input::read_or_skip(day_dir, variant.part) else {
+        return Ok(());
+    };
+
+    let start = Instant::now();
+    black_box((variant.process)(black_box(&input))?);
+    let first = start.elapsed();
+    // Far over the budget, no need to wait for more runs.
+    let (median, runs) = if first > budget.median * 10 {
+        (first, 1)
+    } else {
+        median_time(&input, variant.process)?
+    };
+    if median > budget.median {
+        return Err(AocError::OverBudget {
+            variant: variant.name.to_string(),
+            part: variant.part,
+            median,
+            runs,
+            budget: budget.median,
+        });
+    }
+    Ok(())
+}
+
+/// Declares a test per variant that checks it against the time budget of
+/// its part. Days get these through [`solutions!`](crate::solutions), which
+/// also declares the `SOLUTIONS` and `BUDGETS` they refer to.
+#[macro_export]
+macro_rules! budget_tests {
+    ($($(#[$attr:meta])* $name:ident),* $(,)?) => {
+        #[cfg(test)]
+        mod budget_tests {
+            $(
+                $(#[$attr])*
+                #[test]
+                #[cfg_attr(debug_assertions, ignore = "time budgets only hold in release builds, run `just budgets`")]
+                fn $name() -> Result<(), $crate::AocError> {
+                    let variant = super::SOLUTIONS
+                        .iter()
+                        .find(|variant| variant.name == stringify!($name))
+                        .expect("every variant is in SOLUTIONS");
+                    $crate::budgets::check(
+                        ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+                        variant,
+                        super::BUDGETS,
+                    )
+                }
+            )*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __duration {
+    ($amount:literal s) => {
+        ::std::time::Duration::from_secs($amount)
+    };
+    ($amount:literal ms) => {
+        ::std::time::Duration::from_millis($amount)
+    };
+    ($amount:literal us) => {
+        ::std::time::Duration::from_micros($amount)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::{fs, thread};
+
+    fn quick(input: &str) -> Result<String, AocError> {
+        Ok(input.len().to_string())
+    }
+
+    fn sleepy(_input: &str) -> Result<String, AocError> {
+        thread::sleep(Duration::from_millis(5));
+        Ok(String::new())
+    }
+
+    fn variant(process: Solution, slow: bool) -> Variant {
+        Variant {
+            part: 1,
+            name: "part1",
+            process,
+            slow,
+            stages: None,
+        }
+    }
+
+    fn day() -> tempfile::TempDir {
+        let day = tempfile::tempdir().unwrap();
+        fs::write(day.path().join("input.txt"), "12\n34").unwrap();
+        day
+    }
+
+    const BUDGETS: &[PartBudget] = &[PartBudget {
+        part: 1,
+        median: crate::__duration!(2 ms),
+    }];
+
+    #[test]
+    fn test_median_time() -> miette::Result<()> {
+        let (median, runs) = median_time("", sleepy)?;
+        assert!(median >= Duration::from_millis(5), "{median:?}");
+        assert_eq!(SAMPLES, runs);
+        Ok(())
+    }
+
+    #[test]
+    fn test_within_budget() -> miette::Result<()> {
+        check(day().path(), &variant(quick, false), BUDGETS)?;
+        // Nothing declared for part two.
+        check(day().path(), &Variant { part: 2, ..variant(sleepy, false) }, BUDGETS)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_over_budget() {
+        let error = check(day().path(), &variant(sleepy, false), BUDGETS).unwrap_err();
+        assert!(
+            matches!(error, AocError::OverBudget { part: 1, runs, .. } if runs > 1),
+            "{error:?}"
+        );
+        assert!(error.to_string().ends_with("over its part 1 budget of 2ms"), "{error}");
+    }
+
+    #[test]
+    fn test_slow_variants_have_no_budget() -> miette::Result<()> {
+        check(day().path(), &variant(sleepy, true), BUDGETS)?;
+        Ok(())
+    }
+}
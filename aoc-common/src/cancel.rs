@@ -0,0 +1,84 @@
+//! Cooperative cancellation for solutions run under a time budget.
+//!
+//! A solution grabs the token for its run with [`current`] at the start of
+//! `process`, on the thread it was called on, and calls
+//! [`CancellationToken::check`] in its inner loops. Outside a budgeted run
+//! the token can never be cancelled and `check` is a `None` test.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{runner, AocError};
+
+/// Shared flag the runner sets once a run's time budget is spent. Clones
+/// observe the same flag, so it can be moved into rayon closures.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    flag: Option<Arc<AtomicBool>>,
+}
+
+impl CancellationToken {
+    /// A token that can be cancelled with [`CancellationToken::cancel`].
+    pub fn new() -> Self {
+        Self {
+            flag: Some(Arc::new(AtomicBool::new(false))),
+        }
+    }
+
+    /// Asks every holder of this token to stop.
+    pub fn cancel(&self) {
+        if let Some(flag) = &self.flag {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    /// Returns [`AocError::Cancelled`] once the token has been cancelled,
+    /// so a solution can bail out with `token.check()?`.
+    #[inline]
+    pub fn check(&self) -> Result<(), AocError> {
+        if self.is_cancelled() {
+            Err(AocError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Token of the run executing on this thread, or one that is never
+/// cancelled when there is no budgeted run.
+pub fn current() -> CancellationToken {
+    runner::with_context(|context| {
+        context.map(|context| context.token.clone()).unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(clone.check().is_ok());
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(matches!(clone.check(), Err(AocError::Cancelled)));
+    }
+
+    #[test]
+    fn test_current_outside_run_is_never_cancelled() {
+        let token = current();
+        token.cancel();
+        assert!(!token.is_cancelled());
+    }
+}
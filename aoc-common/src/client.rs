@@ -0,0 +1,500 @@
+//! Client for the Advent of Code website: downloads a day's input and
+//! puzzle text into the day's directory, and submits answers guarded by
+//! the day's [`Ledger`].
+//!
+//! Requests are authenticated with the `session` cookie of a logged-in
+//! browser, looked up the same way `aoc-cli` does (see [`SessionSources`]).
+
+use std::{
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    input,
+    integrity::{self, Fingerprints},
+    ledger::{self, Attempt, Ledger, Verdict},
+    AocError,
+};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies the tool to the Advent of Code servers, as they ask of
+/// automated clients.
+const USER_AGENT: &str =
+    "github.com/CockyAmoeba/advent-of-code (aoc-common client)";
+
+/// Environment variable read for the session cookie.
+pub const SESSION_ENV: &str = "ADVENT_OF_CODE_SESSION";
+const SESSION_FILE_NAME: &str = "adventofcode.session";
+
+/// Value of the `session` cookie. Its `Debug` output is redacted so it
+/// doesn't end up in traces.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Session(String);
+
+impl Session {
+    pub fn new(cookie: impl Into<String>) -> Self {
+        Self(cookie.into().trim().to_string())
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Session(..)")
+    }
+}
+
+/// Places a session cookie can be read from, in order of precedence:
+///
+/// 1. `.adventofcode.session` in the home directory
+/// 2. the file passed with `--session-file`
+/// 3. the `ADVENT_OF_CODE_SESSION` environment variable
+/// 4. `adventofcode.session` in the user's config directory
+#[derive(Debug, Default)]
+pub struct SessionSources {
+    pub home_dir: Option<PathBuf>,
+    pub session_file: Option<PathBuf>,
+    pub env: Option<String>,
+    pub config_dir: Option<PathBuf>,
+}
+
+impl SessionSources {
+    /// Sources of the current user, plus an explicit `session_file`.
+    pub fn from_env(session_file: Option<PathBuf>) -> Self {
+        Self {
+            home_dir: dirs::home_dir(),
+            session_file,
+            env: std::env::var(SESSION_ENV).ok(),
+            config_dir: dirs::config_dir(),
+        }
+    }
+
+    pub fn find(&self) -> Result<Session, AocError> {
+        let home_file = self
+            .home_dir
+            .as_ref()
+            .map(|dir| dir.join(format!(".{SESSION_FILE_NAME}")));
+        let config_file = self
+            .config_dir
+            .as_ref()
+            .map(|dir| dir.join(SESSION_FILE_NAME));
+
+        let from_file = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .and_then(|path| fs::read_to_string(path).ok())
+                .filter(|cookie| !cookie.trim().is_empty())
+        };
+        from_file(&home_file)
+            .or_else(|| from_file(&self.session_file))
+            .or_else(|| self.env.clone().filter(|cookie| !cookie.trim().is_empty()))
+            .or_else(|| from_file(&config_file))
+            .map(Session::new)
+            .ok_or_else(|| AocError::MissingSession {
+                searched: [home_file, self.session_file.clone(), config_file]
+                    .into_iter()
+                    .flatten()
+                    .map(|path| path.display().to_string())
+                    .chain([format!("${SESSION_ENV}")])
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })
+    }
+}
+
+#[derive(Debug)]
+pub struct Client {
+    base_url: String,
+    session: Session,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(session: Session) -> Self {
+        Self {
+            base_url: BASE_URL.to_string(),
+            session,
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+
+    /// Points the client at another server, e.g. a local stand-in in tests.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn url(&self, year: u16, day: u8, path: &str) -> String {
+        format!("{}/{year}/day/{day}{path}", self.base_url)
+    }
+
+    fn get(&self, url: &str) -> Result<String, AocError> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session.0))
+            .call()
+            .map_err(|error| http_error(url, error))?;
+        Ok(response.into_string()?)
+    }
+
+    fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, AocError> {
+        let response = self
+            .agent
+            .post(url)
+            .set("Cookie", &format!("session={}", self.session.0))
+            .send_form(form)
+            .map_err(|error| http_error(url, error))?;
+        Ok(response.into_string()?)
+    }
+
+    /// The puzzle input, exactly as served.
+    pub fn input(&self, year: u16, day: u8) -> Result<String, AocError> {
+        self.get(&self.url(year, day, "/input"))
+    }
+
+    /// The full HTML page of the puzzle.
+    pub fn puzzle_html(&self, year: u16, day: u8) -> Result<String, AocError> {
+        self.get(&self.url(year, day, ""))
+    }
+
+    /// Writes the puzzle description to `puzzle.md` and the input to
+    /// `input.txt` in `dir`, creating it if needed, and records the input's
+    /// fingerprint. An input that is obviously not one is refused.
+    pub fn download(
+        &self,
+        year: u16,
+        day: u8,
+        dir: &Path,
+    ) -> Result<(), AocError> {
+        let puzzle = puzzle_to_markdown(&self.puzzle_html(year, day)?);
+        let input = self.input(year, day)?;
+        let path = dir.join(input::CANONICAL);
+        integrity::check_plausible(&path, &input)?;
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("puzzle.md"), puzzle)?;
+        fs::write(&path, &input)?;
+        Fingerprints::load(dir)?.record(input::CANONICAL, &input::normalize(&input))?;
+        Ok(())
+    }
+
+    /// Sends `answer` to `part` as is, returning the server's verdict.
+    pub fn submit(
+        &self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<Verdict, AocError> {
+        let level = part.to_string();
+        let html = self.post_form(
+            &self.url(year, day, "/answer"),
+            &[("level", &level), ("answer", answer)],
+        )?;
+        Verdict::from_response(&html).ok_or_else(|| AocError::UnrecognisedVerdict {
+            message: puzzle_to_markdown(&html).trim().to_string(),
+        })
+    }
+
+    /// Submits `answer` unless the ledger in `dir` shows it can't be right
+    /// or the server asked us to wait, and records the verdict there.
+    pub fn submit_guarded(
+        &self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+        dir: &Path,
+    ) -> Result<Verdict, AocError> {
+        let answer = answer.trim();
+        let mut ledger = Ledger::load(dir)?;
+        ledger.check(part, answer, ledger::now())?;
+        let verdict = self.submit(year, day, part, answer)?;
+        ledger.record(Attempt {
+            at: ledger::now(),
+            part,
+            verdict,
+            answer: answer.to_string(),
+        })?;
+        Ok(verdict)
+    }
+}
+
+fn http_error(url: &str, error: ureq::Error) -> AocError {
+    match error {
+        ureq::Error::Status(status, response) => AocError::Http {
+            url: url.to_string(),
+            status: Some(status),
+            message: response
+                .into_string()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        },
+        ureq::Error::Transport(transport) => AocError::Http {
+            url: url.to_string(),
+            status: None,
+            message: transport.to_string(),
+        },
+    }
+}
+
+/// Converts a puzzle page to the markdown stored as `puzzle.md`: the
+/// contents of `<main>` (both parts' descriptions and the answer form)
+/// rendered with `html2md`, matching what `aoc-cli` wrote for earlier days.
+pub fn puzzle_to_markdown(html: &str) -> String {
+    let main = html
+        .find("<main>")
+        .zip(html.rfind("</main>"))
+        .filter(|(start, end)| start < end)
+        .map_or(html, |(start, end)| &html[start + "<main>".len()..end]);
+    html2md::parse_html(main)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{sync::mpsc, thread};
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// A request the stand-in server received.
+    #[derive(Debug)]
+    pub(crate) struct Received {
+        pub(crate) method: String,
+        pub(crate) url: String,
+        pub(crate) cookie: Option<String>,
+        pub(crate) user_agent: Option<String>,
+        pub(crate) body: String,
+    }
+
+    /// Serves one canned `(status, body)` response per request, in order,
+    /// on a local port. Returns the base URL and the requests it received.
+    pub(crate) fn serve(
+        responses: Vec<(u16, &'static str)>,
+    ) -> (String, mpsc::Receiver<Received>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let mut request = server.recv().unwrap();
+                let header = |name: &str| {
+                    request
+                        .headers()
+                        .iter()
+                        .find(|header| {
+                            header.field.as_str().as_str().eq_ignore_ascii_case(name)
+                        })
+                        .map(|header| header.value.to_string())
+                };
+                let mut received = Received {
+                    method: request.method().to_string(),
+                    url: request.url().to_string(),
+                    cookie: header("Cookie"),
+                    user_agent: header("User-Agent"),
+                    body: String::new(),
+                };
+                request
+                    .as_reader()
+                    .read_to_string(&mut received.body)
+                    .unwrap();
+                sender.send(received).unwrap();
+                let response = tiny_http::Response::from_string(body)
+                    .with_status_code(status);
+                request.respond(response).unwrap();
+            }
+        });
+        (base_url, receiver)
+    }
+
+    const PUZZLE_HTML: &str = r#"<!DOCTYPE html>
+<html><head><title>Day 1 - Advent of Code 2024</title></head>
+<body><header>Advent of Code</header>
+<main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>The <em>Chief Historian</em> is missing.</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>In the example above, this is <code><em>11</em></code>!</p>
+</article>
+<p>To begin, <a href="1/input" target="_blank">get your puzzle input</a>.</p>
+</main>
+<footer>Footer</footer></body></html>"#;
+
+    #[test]
+    fn test_puzzle_to_markdown() {
+        let markdown = puzzle_to_markdown(PUZZLE_HTML);
+        assert!(
+            markdown.starts_with("\\--- Day 1: Historian Hysteria ---\n----------\n"),
+            "{markdown}"
+        );
+        assert!(markdown.contains("The *Chief Historian* is missing."));
+        assert!(markdown.contains("```\n3   4\n4   3\n"));
+        assert!(markdown.contains("`*11*`"));
+        assert!(markdown.contains("[get your puzzle input](1/input)"));
+        assert!(!markdown.contains("Footer"));
+    }
+
+    #[test]
+    fn test_download() -> miette::Result<()> {
+        let (base_url, requests) =
+            serve(vec![(200, PUZZLE_HTML), (200, "3   4\n4   3\n")]);
+        let dir = tempfile::tempdir().unwrap();
+        let client =
+            Client::new(Session::new("abc123\n")).with_base_url(base_url);
+
+        client.download(2024, 1, dir.path())?;
+
+        let puzzle = requests.recv().unwrap();
+        assert_eq!("GET", puzzle.method);
+        assert_eq!("/2024/day/1", puzzle.url);
+        assert_eq!(Some("session=abc123".to_string()), puzzle.cookie);
+        assert_eq!(Some(USER_AGENT.to_string()), puzzle.user_agent);
+        assert_eq!("/2024/day/1/input", requests.recv().unwrap().url);
+        assert_eq!(
+            "3   4\n4   3\n",
+            fs::read_to_string(dir.path().join("input.txt")).unwrap()
+        );
+        assert!(fs::read_to_string(dir.path().join("puzzle.md"))
+            .unwrap()
+            .contains("Historian Hysteria"));
+        assert!(Fingerprints::load(dir.path())?.get("input.txt").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_refuses_error_page() {
+        let (base_url, _requests) = serve(vec![
+            (200, PUZZLE_HTML),
+            (200, "<!DOCTYPE html>\n<html><body>Oops</body></html>"),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let client = Client::new(Session::new("abc123")).with_base_url(base_url);
+
+        assert!(matches!(
+            client.download(2024, 1, dir.path()),
+            Err(AocError::InvalidInput { .. })
+        ));
+        assert!(!dir.path().join("input.txt").exists());
+    }
+
+    #[test]
+    fn test_bad_session_is_an_http_error() {
+        let message = "Puzzle inputs differ by user.  Please log in to get your puzzle input.";
+        let (base_url, _requests) = serve(vec![(400, message)]);
+        let client = Client::new(Session::new("stale")).with_base_url(base_url);
+
+        match client.input(2023, 5) {
+            Err(AocError::Http {
+                status: Some(400),
+                message: body,
+                ..
+            }) => assert_eq!(message, body),
+            other => panic!("expected an HTTP error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_submit_guarded() -> miette::Result<()> {
+        let (base_url, requests) = serve(vec![
+            (200, "<main><article><p>That's not the right answer; your answer is too high.</p></article></main>"),
+            (200, "<main><article><p>That's the right answer!</p></article></main>"),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let client = Client::new(Session::new("abc123")).with_base_url(base_url);
+
+        assert_eq!(
+            Verdict::TooHigh,
+            client.submit_guarded(2023, 5, 2, "500\n", dir.path())?
+        );
+        let request = requests.recv().unwrap();
+        assert_eq!("POST", request.method);
+        assert_eq!("/2023/day/5/answer", request.url);
+        assert_eq!("level=2&answer=500", request.body);
+
+        // Refused locally; the stand-in would answer "correct" otherwise.
+        assert!(matches!(
+            client.submit_guarded(2023, 5, 2, "600", dir.path()),
+            Err(AocError::SubmissionRefused { .. })
+        ));
+        assert_eq!(
+            Verdict::Correct,
+            client.submit_guarded(2023, 5, 2, "46", dir.path())?
+        );
+        assert_eq!("level=2&answer=46", requests.recv().unwrap().body);
+
+        let ledger = Ledger::load(dir.path())?;
+        assert_eq!(Some("46"), ledger.correct_answer(2));
+        assert_eq!(2, ledger.attempts(2).count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unrecognised_verdict_is_not_recorded() {
+        let (base_url, _requests) =
+            serve(vec![(200, "<main><p>Something new</p></main>")]);
+        let dir = tempfile::tempdir().unwrap();
+        let client = Client::new(Session::new("abc123")).with_base_url(base_url);
+
+        match client.submit_guarded(2023, 5, 1, "35", dir.path()) {
+            Err(AocError::UnrecognisedVerdict { message }) => {
+                assert_eq!("Something new", message)
+            }
+            other => panic!("expected an unrecognised verdict, got {other:?}"),
+        }
+        assert_eq!(0, Ledger::load(dir.path()).unwrap().attempts(1).count());
+    }
+
+    #[test]
+    fn test_session_precedence() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        let config = dir.path().join("config");
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(&config).unwrap();
+        let explicit = dir.path().join("explicit.session");
+        fs::write(&explicit, "from-flag\n").unwrap();
+        fs::write(config.join("adventofcode.session"), "from-config").unwrap();
+
+        let mut sources = SessionSources {
+            home_dir: Some(home.clone()),
+            session_file: None,
+            env: None,
+            config_dir: Some(config),
+        };
+        assert_eq!(Session::new("from-config"), sources.find()?);
+
+        sources.env = Some("from-env".to_string());
+        assert_eq!(Session::new("from-env"), sources.find()?);
+
+        sources.session_file = Some(explicit);
+        assert_eq!(Session::new("from-flag"), sources.find()?);
+
+        fs::write(home.join(".adventofcode.session"), "from-home").unwrap();
+        assert_eq!(Session::new("from-home"), sources.find()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_session_lists_places_searched() {
+        let sources = SessionSources {
+            home_dir: Some(PathBuf::from("/nowhere")),
+            ..Default::default()
+        };
+        match sources.find() {
+            Err(AocError::MissingSession { searched }) => {
+                assert!(searched.contains("/nowhere/.adventofcode.session"));
+                assert!(searched.contains("$ADVENT_OF_CODE_SESSION"));
+            }
+            other => panic!("expected a missing session, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_session_debug_is_redacted() {
+        assert_eq!("Session(..)", format!("{:?}", Session::new("secret")));
+    }
+}
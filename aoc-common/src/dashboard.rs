@@ -0,0 +1,453 @@
+//! One static HTML page of the benchmark results of every year, rather than
+//! divan's text tables and criterion's reports scattered under `target/`.
+//!
+//! [`Dashboard::load`] collects each year's `bench-history.tsv` (see
+//! [`bench_history`](crate::bench_history)) and [`Dashboard::render`] draws
+//! it with plotters as inline SVG: the total time of every year with its
+//! fastest variants, the latest median of every variant of a day side by
+//! side, and how those medians moved from run to run. `just bench-report`
+//! writes it to `bench-report.html` at the root of the repository.
+//!
+//! Only the full pipeline on the real input is shown, that is the
+//! `solutions::<variant>` benchmarks; scaling and stage benchmarks stay in
+//! the history for `bench-compare`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write as _},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use plotters::prelude::*;
+
+use crate::{
+    allocations::format_bytes,
+    bench_history::{History, Key, Run, HISTORY_FILE},
+    AocError,
+};
+
+const WIDTH: u32 = 900;
+
+/// A variant's median in one recorded run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    pub at: u64,
+    pub rev: String,
+    pub median: Duration,
+    /// Peak heap in bytes, if divan's allocation profiler reported it.
+    pub peak: Option<u64>,
+}
+
+/// The recorded results of a year, by variant, oldest first.
+#[derive(Debug)]
+pub struct Year {
+    pub year: u16,
+    pub variants: BTreeMap<Key, Vec<Point>>,
+}
+
+impl Year {
+    pub fn from_runs(year: u16, runs: &[Run]) -> Self {
+        let mut variants = BTreeMap::<Key, Vec<Point>>::new();
+        for run in runs {
+            if let Some(key) = Key::of(&run.measurement.bench) {
+                variants.entry(key).or_default().push(Point {
+                    at: run.at,
+                    rev: run.rev.clone(),
+                    median: run.measurement.median,
+                    peak: run.measurement.peak,
+                });
+            }
+        }
+        Self { year, variants }
+    }
+
+    pub fn days(&self) -> BTreeSet<u8> {
+        self.variants.keys().map(|key| key.day).collect()
+    }
+
+    fn latest(&self, day: u8) -> impl Iterator<Item = (&Key, &Point)> {
+        self.variants
+            .iter()
+            .filter(move |(key, _)| key.day == day)
+            .filter_map(|(key, points)| Some((key, points.last()?)))
+    }
+
+    /// Latest median of the fastest variant of each part of `day`, summed.
+    pub fn day_total(&self, day: u8) -> Duration {
+        let mut fastest = BTreeMap::<u8, Duration>::new();
+        for (key, point) in self.latest(day) {
+            fastest
+                .entry(key.part)
+                .and_modify(|median| *median = (*median).min(point.median))
+                .or_insert(point.median);
+        }
+        fastest.values().sum()
+    }
+
+    /// How long the whole year takes with its fastest variants.
+    pub fn total(&self) -> Duration {
+        self.days().into_iter().map(|day| self.day_total(day)).sum()
+    }
+}
+
+#[derive(Debug)]
+pub struct Dashboard {
+    pub years: Vec<Year>,
+}
+
+impl Dashboard {
+    /// Loads the history of every year among `dirs`, skipping directories
+    /// not named after a year and years without results.
+    pub fn load(dirs: &[PathBuf]) -> Result<Self, AocError> {
+        let mut years = Vec::new();
+        for dir in dirs {
+            let Some(year) = year_of(dir) else {
+                continue;
+            };
+            let history = History::load(&dir.join(HISTORY_FILE))?;
+            let year = Year::from_runs(year, history.runs());
+            if !year.variants.is_empty() {
+                years.push(year);
+            }
+        }
+        years.sort_by_key(|year| year.year);
+        Ok(Self { years })
+    }
+
+    /// The page, with its charts inline.
+    pub fn render(&self) -> Result<String, AocError> {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Advent of Code benchmarks</title>\n<style>\n\
+             body { font-family: sans-serif; max-width: 960px; margin: 2em auto; }\n\
+             table { border-collapse: collapse; margin: 1em 0; }\n\
+             th, td { padding: 2px 10px; text-align: right; border-bottom: 1px solid #ddd; }\n\
+             th:first-child, td:first-child { text-align: left; }\n\
+             </style>\n</head>\n<body>\n<h1>Advent of Code benchmarks</h1>\n",
+        );
+        if self.years.is_empty() {
+            html.push_str("<p>No results recorded yet, run <code>just bench-all</code> in a year first.</p>\n");
+        } else {
+            html.push_str(
+                "<p>Medians of the latest divan run of every variant on the real input, \
+                 from each year's <code>bench-history.tsv</code>.</p>\n<h2>Total per year</h2>\n",
+            );
+            html.push_str(&totals_chart(&self.years)?);
+            html.push_str("<table>\n<tr><th>Year</th><th>Days</th><th>Total</th></tr>\n");
+            for year in &self.years {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    year.year,
+                    year.days().len(),
+                    format_duration(year.total())
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        for year in &self.years {
+            let _ = writeln!(html, "<h2>{}</h2>", year.year);
+            for day in year.days() {
+                let _ = writeln!(html, "<h3>Day {day}</h3>");
+                html.push_str(&variants_chart(year, day)?);
+                html.push_str(&trend_chart(year, day)?);
+                html.push_str(
+                    "<table>\n<tr><th>Variant</th><th>Median</th><th>Peak heap</th><th>Runs</th><th>Revision</th></tr>\n",
+                );
+                for (key, point) in year.latest(day) {
+                    let _ = writeln!(
+                        html,
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        escape(&key.variant),
+                        format_duration(point.median),
+                        point.peak.map_or_else(|| "-".to_string(), format_bytes),
+                        year.variants[key].len(),
+                        escape(&point.rev)
+                    );
+                }
+                html.push_str("</table>\n");
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        Ok(html)
+    }
+}
+
+/// Year of a directory named like `2023`.
+fn year_of(dir: &Path) -> Option<u16> {
+    dir.file_name()?.to_str()?.parse().ok()
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = micros(duration);
+    if micros >= 1e3 {
+        format!("{:.2} ms", micros / 1e3)
+    } else {
+        format!("{micros:.1} µs")
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn chart_error(chart: &str, error: impl fmt::Display) -> AocError {
+    AocError::Chart {
+        chart: chart.to_string(),
+        message: error.to_string(),
+    }
+}
+
+/// One bar per year, the sum of its days' fastest variants.
+fn totals_chart(years: &[Year]) -> Result<String, AocError> {
+    let name = "total per year";
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, (WIDTH, 300)).into_drawing_area();
+        root.fill(&WHITE).map_err(|error| chart_error(name, error))?;
+        let max = years.iter().map(|year| micros(year.total())).fold(0.0, f64::max);
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(70)
+            .build_cartesian_2d(-0.5..years.len() as f64 - 0.5, 0.0..max * 1.15)
+            .map_err(|error| chart_error(name, error))?;
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_labels(years.len())
+            .x_label_formatter(&|x| {
+                years
+                    .get(x.round() as usize)
+                    .map(|year| year.year.to_string())
+                    .unwrap_or_default()
+            })
+            .y_label_formatter(&|y| format!("{y:.0}"))
+            .y_desc("µs")
+            .draw()
+            .map_err(|error| chart_error(name, error))?;
+        chart
+            .draw_series(years.iter().enumerate().map(|(idx, year)| {
+                let x = idx as f64;
+                Rectangle::new(
+                    [(x - 0.3, 0.0), (x + 0.3, micros(year.total()))],
+                    Palette99::pick(idx).filled(),
+                )
+            }))
+            .map_err(|error| chart_error(name, error))?;
+        chart
+            .draw_series(years.iter().enumerate().map(|(idx, year)| {
+                Text::new(
+                    format_duration(year.total()),
+                    (idx as f64 - 0.1, micros(year.total()) + max * 0.07),
+                    ("sans-serif", 14),
+                )
+            }))
+            .map_err(|error| chart_error(name, error))?;
+        root.present().map_err(|error| chart_error(name, error))?;
+    }
+    Ok(svg)
+}
+
+/// A horizontal bar per variant of `day`, labelled with its latest median.
+fn variants_chart(year: &Year, day: u8) -> Result<String, AocError> {
+    let name = format!("{} day {day} variants", year.year);
+    let latest = year.latest(day).collect::<Vec<_>>();
+    let mut svg = String::new();
+    {
+        let height = 40 + 28 * latest.len() as u32;
+        let root = SVGBackend::with_string(&mut svg, (WIDTH, height)).into_drawing_area();
+        root.fill(&WHITE).map_err(|error| chart_error(&name, error))?;
+        let max = latest
+            .iter()
+            .map(|(_, point)| micros(point.median))
+            .fold(0.0, f64::max);
+        // Room to the right of the longest bar for its label.
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .x_label_area_size(25)
+            .build_cartesian_2d(0.0..max * 1.6, 0.0..latest.len() as f64)
+            .map_err(|error| chart_error(&name, error))?;
+        chart
+            .configure_mesh()
+            .disable_y_mesh()
+            .y_labels(0)
+            .x_label_formatter(&|x| format!("{x:.0}"))
+            .x_desc("µs")
+            .draw()
+            .map_err(|error| chart_error(&name, error))?;
+        chart
+            .draw_series(latest.iter().enumerate().map(|(idx, (key, point))| {
+                let y = (latest.len() - idx - 1) as f64;
+                Rectangle::new(
+                    [(0.0, y + 0.15), (micros(point.median), y + 0.85)],
+                    Palette99::pick(usize::from(key.part) - 1).filled(),
+                )
+            }))
+            .map_err(|error| chart_error(&name, error))?;
+        chart
+            .draw_series(latest.iter().enumerate().map(|(idx, (key, point))| {
+                let y = (latest.len() - idx - 1) as f64;
+                Text::new(
+                    format!("{} {}", key.variant, format_duration(point.median)),
+                    (micros(point.median) + max * 0.02, y + 0.7),
+                    ("sans-serif", 14),
+                )
+            }))
+            .map_err(|error| chart_error(&name, error))?;
+        root.present().map_err(|error| chart_error(&name, error))?;
+    }
+    Ok(svg)
+}
+
+/// A line per variant of `day` through its median in every recorded run,
+/// one step per run of the year, labelled with its revision.
+fn trend_chart(year: &Year, day: u8) -> Result<String, AocError> {
+    let name = format!("{} day {day} history", year.year);
+    let runs = year
+        .variants
+        .values()
+        .flatten()
+        .map(|point| (point.at, point.rev.as_str()))
+        .collect::<BTreeMap<_, _>>();
+    let step = |at: u64| runs.keys().position(|&run| run == at).unwrap_or_default() as f64;
+    let revs = runs.values().copied().collect::<Vec<_>>();
+    let variants = year
+        .variants
+        .iter()
+        .filter(|(key, _)| key.day == day)
+        .collect::<Vec<_>>();
+    let max = variants
+        .iter()
+        .flat_map(|(_, points)| points.iter().map(|point| micros(point.median)))
+        .fold(0.0, f64::max);
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, (WIDTH, 320)).into_drawing_area();
+        root.fill(&WHITE).map_err(|error| chart_error(&name, error))?;
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(70)
+            .build_cartesian_2d(-0.5..revs.len() as f64 - 0.5, 0.0..max * 1.15)
+            .map_err(|error| chart_error(&name, error))?;
+        chart
+            .configure_mesh()
+            .x_labels(revs.len().min(12))
+            .x_label_formatter(&|x| {
+                let step = x.round();
+                if (x - step).abs() > 0.01 {
+                    return String::new();
+                }
+                revs.get(step as usize).copied().unwrap_or_default().to_string()
+            })
+            .y_label_formatter(&|y| format!("{y:.0}"))
+            .y_desc("µs")
+            .draw()
+            .map_err(|error| chart_error(&name, error))?;
+        for (idx, (key, points)) in variants.iter().enumerate() {
+            let color = Palette99::pick(idx).to_rgba();
+            let line = points
+                .iter()
+                .map(|point| (step(point.at), micros(point.median)))
+                .collect::<Vec<_>>();
+            chart
+                .draw_series(LineSeries::new(line.clone(), color.stroke_width(2)))
+                .map_err(|error| chart_error(&name, error))?
+                .label(key.variant.clone())
+                .legend(move |(x, y)| {
+                    PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2))
+                });
+            chart
+                .draw_series(
+                    line.into_iter()
+                        .map(|point| Circle::new(point, 3, color.filled())),
+                )
+                .map_err(|error| chart_error(&name, error))?;
+        }
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+            .map_err(|error| chart_error(&name, error))?;
+        root.present().map_err(|error| chart_error(&name, error))?;
+    }
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench_history::Measurement;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn run(at: u64, bench: &str, micros: u64) -> Run {
+        let median = Duration::from_micros(micros);
+        Run {
+            at,
+            rev: format!("rev{at}"),
+            measurement: Measurement {
+                bench: bench.to_string(),
+                fastest: median,
+                slowest: median,
+                median,
+                mean: median,
+                samples: 100,
+                iters: 100,
+                peak: Some(micros * 1000),
+            },
+        }
+    }
+
+    fn runs() -> Vec<Run> {
+        vec![
+            run(1, "day_1_bench::solutions::part1", 90),
+            run(1, "day_1_bench::solutions::part1_nom", 170),
+            run(1, "day_1_bench::solutions::part2", 130),
+            run(1, "day_1_bench::stages::part2/parse", 10),
+            run(2, "day_1_bench::solutions::part1", 80),
+            run(2, "day_2_bench::solutions::part1", 50),
+        ]
+    }
+
+    #[test]
+    fn test_totals() {
+        let year = Year::from_runs(2023, &runs());
+        assert_eq!(4, year.variants.len());
+        assert_eq!(Duration::from_micros(80 + 130), year.day_total(1));
+        assert_eq!(Duration::from_micros(80 + 130 + 50), year.total());
+    }
+
+    #[test]
+    fn test_render() -> miette::Result<()> {
+        let root = tempfile::tempdir().unwrap();
+        let year_dir = root.path().join("2023");
+        fs::create_dir(&year_dir).unwrap();
+        History::load(&year_dir.join(HISTORY_FILE))?.record(1, "abc1234", &[runs()[0].measurement.clone()])?;
+        History::load(&year_dir.join(HISTORY_FILE))?.record(2, "def5678", &[runs()[4].measurement.clone()])?;
+        // Neither a year nor one with results.
+        fs::create_dir(root.path().join("aoc-common")).unwrap();
+        fs::create_dir(root.path().join("2024")).unwrap();
+
+        let dashboard = Dashboard::load(&[
+            year_dir,
+            root.path().join("aoc-common"),
+            root.path().join("2024"),
+        ])?;
+        assert_eq!(1, dashboard.years.len());
+        let html = dashboard.render()?;
+        assert_eq!(3, html.matches("<svg").count());
+        assert!(html.contains("<td>part1</td><td>80.0 µs</td><td>80 KB</td><td>2</td><td>def5678</td>"), "{html}");
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use std::time::Duration;
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Error shared by every day's solutions and the tooling that runs them.
+/// Each day re-exports it from its `custom_error` module.
+#[derive(Error, Diagnostic, Debug)]
+pub enum AocError {
+    #[error(transparent)]
+    #[diagnostic(code(aoc::io_error))]
+    IoError(#[from] std::io::Error),
+
+    #[error("run was cancelled")]
+    #[diagnostic(code(aoc::cancelled))]
+    Cancelled,
+
+    #[error(
+        "timed out after {elapsed:.2?} with a budget of {budget:?}, {}",
+        describe_progress(.progress)
+    )]
+    #[diagnostic(
+        code(aoc::timed_out),
+        help("raise AOC_TIME_BUDGET, or check `cancel::current()` more often if the solution kept running past its budget")
+    )]
+    TimedOut {
+        budget: Duration,
+        elapsed: Duration,
+        /// Units of work done and in total, if the solution reported any
+        /// through `progress::Progress`.
+        progress: Option<(u64, u64)>,
+    },
+
+    #[error("invalid time budget {value:?}")]
+    #[diagnostic(
+        code(aoc::invalid_budget),
+        help("set AOC_TIME_BUDGET to a number of seconds, e.g. `2.5`")
+    )]
+    InvalidBudget { value: String },
+
+    #[error("no Advent of Code session cookie found, looked in {searched}")]
+    #[diagnostic(
+        code(aoc::missing_session),
+        help("copy the `session` cookie from a logged-in browser into one of those places, see the justfile")
+    )]
+    MissingSession { searched: String },
+
+    #[error("request to {url} failed{}: {message}", .status.map(|status| format!(" with status {status}")).unwrap_or_default())]
+    #[diagnostic(
+        code(aoc::http),
+        help("a 400 or 500 usually means the session cookie has expired, and a 404 that the puzzle isn't unlocked yet")
+    )]
+    Http {
+        url: String,
+        /// Status code, or `None` if no response came back.
+        status: Option<u16>,
+        message: String,
+    },
+
+    #[error("refusing to submit {answer:?}: {reason}")]
+    #[diagnostic(
+        code(aoc::submission_refused),
+        help("see the day's submissions.tsv for every answer tried so far")
+    )]
+    SubmissionRefused { answer: String, reason: String },
+
+    #[error("unrecognised reply to a submission: {message}")]
+    #[diagnostic(
+        code(aoc::unrecognised_verdict),
+        help("the answer may or may not have been judged; check the puzzle page before submitting again")
+    )]
+    UnrecognisedVerdict { message: String },
+
+    #[error("{path} line {line} is not `unix time, part, verdict, answer`")]
+    #[diagnostic(code(aoc::invalid_ledger))]
+    InvalidLedger { path: String, line: usize },
+
+    #[error("could not read {path}: {reason}")]
+    #[diagnostic(
+        code(aoc::invalid_puzzle),
+        help("download the puzzle again with `just download N`")
+    )]
+    InvalidPuzzle { path: String, reason: String },
+
+    #[error("inputs are encrypted and no key was found, looked in {searched}")]
+    #[diagnostic(
+        code(aoc::missing_input_key),
+        help("ask for the key and save it to one of those places, or create one with `aoc-tool vault keygen` if none exists yet")
+    )]
+    MissingInputKey { searched: String },
+
+    #[error("the input key in {origin} is not 64 hex digits")]
+    #[diagnostic(code(aoc::invalid_input_key))]
+    InvalidInputKey { origin: String },
+
+    #[error("could not decrypt {path}")]
+    #[diagnostic(
+        code(aoc::undecryptable_input),
+        help("the file was sealed with a different key, or is corrupt")
+    )]
+    UndecryptableInput { path: String },
+
+    #[error("{path} is not a puzzle input: {reason}")]
+    #[diagnostic(
+        code(aoc::invalid_input),
+        help("download it again with `just download N`, after checking the session cookie")
+    )]
+    InvalidInput { path: String, reason: String },
+
+    #[error("line {line} of the input {reason}")]
+    #[diagnostic(
+        code(aoc::malformed_input),
+        help("check the input has no CRLF line endings or stray characters, and every line has the shape of the first")
+    )]
+    MalformedInput { line: usize, reason: String },
+
+    #[error("{path} changed since it was recorded: it had {recorded}, now {actual}")]
+    #[diagnostic(
+        code(aoc::input_changed),
+        help("answers recorded for it may no longer hold; restore it, or run `just fingerprint-inputs` if the change is intended")
+    )]
+    InputChanged {
+        path: String,
+        recorded: String,
+        actual: String,
+    },
+
+    #[error("{path} line {line} is not `name, sha256, lines, width, classes`")]
+    #[diagnostic(code(aoc::invalid_fingerprints))]
+    InvalidFingerprints { path: String, line: usize },
+
+    #[error("part {part} on the example in puzzle.md {variant} returned {actual:?}, expected {expected:?}")]
+    #[diagnostic(code(aoc::wrong_example_answer))]
+    WrongExampleAnswer {
+        part: u8,
+        /// How the example was written out, e.g. `with CRLF line endings`.
+        variant: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("{path} line {line} is not `part answer`")]
+    #[diagnostic(code(aoc::invalid_answers))]
+    InvalidAnswers { path: String, line: usize },
+
+    #[error("{path} line {line} is not `unix time, rev, bench, fastest, slowest, median, mean, samples, iters, peak`")]
+    #[diagnostic(code(aoc::invalid_bench_history))]
+    InvalidBenchHistory { path: String, line: usize },
+
+    #[error("{regressed} benchmarks got slower than their baseline")]
+    #[diagnostic(
+        code(aoc::bench_regressed),
+        help("run the benchmarks again to rule out a noisy machine before digging in")
+    )]
+    BenchRegressed { regressed: usize },
+
+    #[error("could not draw the {chart} chart: {message}")]
+    #[diagnostic(code(aoc::chart))]
+    Chart { chart: String, message: String },
+
+    #[error("{variant} took a median of {median:.2?} over {runs} runs, over its part {part} budget of {budget:?}")]
+    #[diagnostic(
+        code(aoc::over_budget),
+        help("profile it with `just bench`, or raise the part's budget in the day's `solutions!` if the year still fits in a second")
+    )]
+    OverBudget {
+        variant: String,
+        part: u8,
+        median: Duration,
+        runs: usize,
+        budget: Duration,
+    },
+
+    #[error("solution panicked: {message}")]
+    #[diagnostic(code(aoc::panicked))]
+    Panicked { message: String },
+
+    #[error("invalid output format {value:?}")]
+    #[diagnostic(
+        code(aoc::invalid_output_format),
+        help("set AOC_OUTPUT to `json` for JSON lines or `csv`, or leave it unset for the bare answer")
+    )]
+    InvalidOutputFormat { value: String },
+
+    #[error("{failed} of {total} runs got a wrong answer or failed")]
+    #[diagnostic(code(aoc::verification_failed))]
+    VerificationFailed { failed: usize, total: usize },
+}
+
+fn describe_progress(progress: &Option<(u64, u64)>) -> String {
+    match progress {
+        Some((done, total)) if *total > 0 => format!(
+            "{done}/{total} units of work done ({:.1}%)",
+            *done as f64 * 100.0 / *total as f64
+        ),
+        Some((done, _)) => format!("{done} units of work done"),
+        None => "no progress reported".to_string(),
+    }
+}
@@ -0,0 +1,182 @@
+//! Finds a day's puzzle input.
+//!
+//! Both parts of a puzzle share one input, so each day keeps a single
+//! canonical `input.txt`. A part reads `input1.txt` or `input2.txt`
+//! instead only if that file exists, for the rare day whose parts really do
+//! differ. Either may be sealed (see [`crate::vault`]).
+//!
+//! Inputs are [normalised](normalize) as they're read, so a day can split
+//! on `'\n'` without tripping over a BOM, CRLF line endings or a trailing
+//! blank line left by an editor, and [checked](crate::integrity) before
+//! any solution sees them.
+
+use std::{io, path::Path};
+
+use crate::{
+    integrity,
+    vault::{self, sealed_path},
+    AocError,
+};
+
+pub const CANONICAL: &str = "input.txt";
+
+/// Name of the file `part` overrides the canonical input with.
+pub fn override_name(part: u8) -> String {
+    format!("input{part}.txt")
+}
+
+/// Name of the input `part` reads in `day_dir`.
+pub fn name_for_part(day_dir: &Path, part: u8) -> String {
+    let part_specific = override_name(part);
+    let path = day_dir.join(&part_specific);
+    if path.exists() || sealed_path(&path).exists() {
+        part_specific
+    } else {
+        CANONICAL.to_string()
+    }
+}
+
+/// Strips a leading BOM, turns CRLF line endings into LF and drops
+/// trailing newlines.
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    input.replace("\r\n", "\n").trim_end_matches('\n').to_string()
+}
+
+/// Reads the input of `part` in `day_dir`, [normalised](normalize) and
+/// [checked](integrity::check).
+pub fn read(day_dir: &Path, part: u8) -> Result<String, AocError> {
+    let name = name_for_part(day_dir, part);
+    let input = normalize(&vault::read_input(day_dir, &name)?);
+    integrity::check(day_dir, &name, &input)?;
+    Ok(input)
+}
+
+/// Reads the input of `part` for a test, or returns `None`, with a note on
+/// stderr, when the day has no input yet, neither plain nor sealed. Every
+/// other error panics, a sealed input without its key included, so a
+/// checkout that can't open its inputs fails instead of checking nothing.
+pub fn read_or_skip(day_dir: &Path, part: u8) -> Option<String> {
+    match read(day_dir, part) {
+        Ok(input) => Some(input),
+        Err(AocError::IoError(error)) if error.kind() == io::ErrorKind::NotFound => {
+            eprintln!("no part {part} input, skipping the test on it");
+            None
+        }
+        Err(error) => panic!("reading the part {part} input: {error}"),
+    }
+}
+
+/// Reads the input of a part of the calling crate through [`read`], e.g.
+/// `aoc_common::input!(1)?`.
+#[macro_export]
+macro_rules! input {
+    ($part:expr) => {
+        $crate::input::read(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")), $part)
+    };
+}
+
+/// Reads the input of a part of the calling crate for a test through
+/// [`read_or_skip`]:
+///
+/// ```ignore
+/// let Some(input) = aoc_common::real_input!(1) else {
+///     return Ok(());
+/// };
+/// ```
+#[macro_export]
+macro_rules! real_input {
+    ($part:expr) => {
+        $crate::input::read_or_skip(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")), $part)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::vault::{seal, Key};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parts_share_canonical_input() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CANONICAL), "shared").unwrap();
+        assert_eq!("shared", read(dir.path(), 1)?);
+        assert_eq!("shared", read(dir.path(), 2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_override() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CANONICAL), "shared").unwrap();
+        fs::write(dir.path().join("input2.txt"), "part two").unwrap();
+        assert_eq!("shared", read(dir.path(), 1)?);
+        assert_eq!("part two", read(dir.path(), 2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!("a\nb", normalize("\u{feff}a\r\nb\r\n\r\n"));
+        assert_eq!("a\n\nb", normalize("a\n\nb\n"));
+        assert_eq!("  a  ", normalize("  a  "));
+        assert_eq!("", normalize("\n"));
+    }
+
+    #[test]
+    fn test_read_normalizes() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CANONICAL), "1\r\n2\r\n").unwrap();
+        assert_eq!("1\n2", read(dir.path(), 1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_checks_integrity() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CANONICAL), "1 2\n").unwrap();
+        integrity::Fingerprints::load(dir.path())?.record(CANONICAL, "1 2")?;
+        assert_eq!("1 2", read(dir.path(), 1)?);
+
+        fs::write(dir.path().join(CANONICAL), "1 2\n3").unwrap();
+        assert!(matches!(read(dir.path(), 1), Err(AocError::InputChanged { .. })));
+        fs::write(dir.path().join(CANONICAL), "").unwrap();
+        assert!(matches!(read(dir.path(), 1), Err(AocError::InvalidInput { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_or_skip_without_input() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(None, read_or_skip(dir.path(), 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "reading the part 1 input")]
+    fn test_read_or_skip_fails_on_sealed_input_it_cannot_open() {
+        let dir = tempfile::tempdir().unwrap();
+        // Sealed with a key nobody has, so it fails with or without a key
+        // configured on this machine.
+        fs::write(
+            dir.path().join(sealed_path(Path::new(CANONICAL))),
+            seal(&Key::generate(), b"sealed"),
+        )
+        .unwrap();
+        read_or_skip(dir.path(), 1);
+    }
+
+    #[test]
+    fn test_sealed_override_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("input1.txt.enc"),
+            seal(&Key::generate(), b"part one"),
+        )
+        .unwrap();
+        assert_eq!("input1.txt", name_for_part(dir.path(), 1));
+        assert_eq!(CANONICAL, name_for_part(dir.path(), 2));
+    }
+}
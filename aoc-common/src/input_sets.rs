@@ -0,0 +1,196 @@
+//! Inputs of several accounts for one day, each with its expected answers.
+//!
+//! Every account gets different inputs, and a solution that only works on
+//! one of them is a bug. Besides the day's own input, other accounts' inputs
+//! live in `day-N/inputs/<account>/`, laid out like a day directory (an
+//! `input.txt`, optionally sealed, and part overrides if needed) next to an
+//! `answers.txt` of `part answer` lines:
+//!
+//! ```text
+//! day-1/inputs/alice/input.txt.enc
+//! day-1/inputs/alice/answers.txt     1 54953
+//!                                    2 53868
+//! ```
+//!
+//! The day's own input is the set named [`DEFAULT_SET`], with the answers
+//! recorded for it in the year's [manifest](crate::answers). `puzzle.md`
+//! only fills in parts the manifest has no answer for.
+
+use std::{collections::BTreeMap, fs, io, path::{Path, PathBuf}};
+
+use crate::{
+    answers::{self, Manifest},
+    input,
+    puzzle::Puzzle,
+    AocError,
+};
+
+pub const SETS_DIR: &str = "inputs";
+pub const ANSWERS_FILE: &str = "answers.txt";
+pub const DEFAULT_SET: &str = "default";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputSet {
+    pub name: String,
+    /// Directory holding the set's input files.
+    pub dir: PathBuf,
+    /// Expected answer per part.
+    pub answers: BTreeMap<u8, String>,
+}
+
+impl InputSet {
+    /// The day's own input, then every account under `inputs/` by name.
+    pub fn load_all(day_dir: &Path) -> Result<Vec<Self>, AocError> {
+        let mut sets = vec![Self::default_set(day_dir)?];
+
+        let mut accounts = match fs::read_dir(day_dir.join(SETS_DIR)) {
+            Ok(entries) => entries
+                .map(|entry| Ok(entry?.path()))
+                .collect::<Result<Vec<_>, io::Error>>()?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(error.into()),
+        };
+        accounts.retain(|path| path.is_dir());
+        accounts.sort();
+        for dir in accounts {
+            sets.push(Self {
+                name: dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                answers: read_answers(&dir.join(ANSWERS_FILE))?,
+                dir,
+            });
+        }
+        Ok(sets)
+    }
+
+    fn default_set(day_dir: &Path) -> Result<Self, AocError> {
+        let mut answers: BTreeMap<u8, String> = match Puzzle::load(day_dir) {
+            Ok(puzzle) => puzzle
+                .parts
+                .into_iter()
+                .filter_map(|part| Some((part.number, part.answer?)))
+                .collect(),
+            Err(AocError::IoError(error)) if error.kind() == io::ErrorKind::NotFound => {
+                BTreeMap::new()
+            }
+            Err(error) => return Err(error),
+        };
+        if let (Some(day), Some(year_dir)) = (answers::day_of(day_dir), day_dir.parent()) {
+            let manifest = Manifest::load(year_dir)?;
+            answers.extend(manifest.day(day).map(|(part, answer)| (part, answer.to_string())));
+        }
+        Ok(Self {
+            name: DEFAULT_SET.to_string(),
+            dir: day_dir.to_path_buf(),
+            answers,
+        })
+    }
+
+    pub fn input(&self, part: u8) -> Result<String, AocError> {
+        input::read(&self.dir, part)
+    }
+}
+
+fn read_answers(path: &Path) -> Result<BTreeMap<u8, String>, AocError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(error) => return Err(error.into()),
+    };
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            line.trim()
+                .split_once(char::is_whitespace)
+                .and_then(|(part, answer)| Some((part.parse().ok()?, answer.trim().to_string())))
+                .ok_or_else(|| AocError::InvalidAnswers {
+                    path: path.display().to_string(),
+                    line: idx + 1,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// A `puzzle.md` with part one solved and part two still open.
+    const PUZZLE: &str = "\\--- Day 3: Test ---\n----------\n\nIt is `*4*`.\n\n\
+        Your puzzle answer was `11`.\n\n\\--- Part Two ---\n----------\n\nNow `*8*`.\n\nAnswer:\n";
+
+    #[test]
+    fn test_load_all() -> miette::Result<()> {
+        let day = tempfile::tempdir().unwrap();
+        fs::write(day.path().join("input.txt"), "mine").unwrap();
+        fs::write(day.path().join("puzzle.md"), PUZZLE).unwrap();
+        for (account, answers) in [("bob", "1 7\n\n2 a,b\n"), ("alice", "1 5\n")] {
+            let dir = day.path().join(SETS_DIR).join(account);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("input.txt"), account).unwrap();
+            fs::write(dir.join(ANSWERS_FILE), answers).unwrap();
+        }
+
+        let sets = InputSet::load_all(day.path())?;
+        let summary = sets
+            .iter()
+            .map(|set| (set.name.as_str(), set.answers.clone().into_iter().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (DEFAULT_SET, vec![(1, "11".to_string())]),
+                ("alice", vec![(1, "5".to_string())]),
+                ("bob", vec![(1, "7".to_string()), (2, "a,b".to_string())]),
+            ],
+            summary
+        );
+        assert_eq!("mine", sets[0].input(1)?);
+        assert_eq!("bob", sets[2].input(2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_set_answers_from_manifest() -> miette::Result<()> {
+        let year = tempfile::tempdir().unwrap();
+        let day = year.path().join("day-3");
+        fs::create_dir_all(&day).unwrap();
+        fs::write(day.join("puzzle.md"), PUZZLE).unwrap();
+        fs::write(year.path().join(answers::MANIFEST), "3 2 34\n4 1 56\n").unwrap();
+
+        // Part one isn't in the manifest, so its answer comes from puzzle.md.
+        let sets = InputSet::load_all(&day)?;
+        assert_eq!(
+            BTreeMap::from([(1, "11".to_string()), (2, "34".to_string())]),
+            sets[0].answers
+        );
+
+        fs::write(year.path().join(answers::MANIFEST), "3 1 12\n3 2 34\n").unwrap();
+        let sets = InputSet::load_all(&day)?;
+        assert_eq!(Some(&"12".to_string()), sets[0].answers.get(&1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_without_sets_or_puzzle() -> miette::Result<()> {
+        let day = tempfile::tempdir().unwrap();
+        let sets = InputSet::load_all(day.path())?;
+        assert_eq!(1, sets.len());
+        assert!(sets[0].answers.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_answers() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(ANSWERS_FILE), "1 5\none\n").unwrap();
+        assert!(matches!(
+            read_answers(&dir.path().join(ANSWERS_FILE)),
+            Err(AocError::InvalidAnswers { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,274 @@
+//! Sanity checks on stored inputs, so that a truncated download or an error
+//! page saved as the input fails with a clear error instead of a panic deep
+//! inside a parser.
+//!
+//! Every read through [`input::read`](crate::input::read) rejects inputs
+//! that are empty or look like a web page, and compares the input with the
+//! fingerprint recorded for it in the day's `fingerprints.tsv`, written on
+//! download: a hash of the [normalised](crate::input::normalize) text and
+//! its shape, one `name\tsha256\tlines\twidth\tclasses` line per input. An
+//! input that no longer matches has changed since its answers were
+//! recorded.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::AocError;
+
+pub const FINGERPRINTS_FILE: &str = "fingerprints.tsv";
+
+/// Rough outline of an input, shown when it changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shape {
+    pub lines: usize,
+    /// Length of the longest line, in characters.
+    pub width: usize,
+    /// Kinds of characters used, e.g. `digit,lower,space`.
+    pub classes: String,
+}
+
+impl Shape {
+    pub fn of(input: &str) -> Self {
+        let mut used = [false; CLASSES.len()];
+        for c in input.chars().filter(|&c| c != '\n') {
+            used[class_of(c)] = true;
+        }
+        Self {
+            lines: input.lines().count(),
+            width: input.lines().map(|line| line.chars().count()).max().unwrap_or_default(),
+            classes: CLASSES
+                .iter()
+                .zip(used)
+                .filter_map(|(class, used)| used.then_some(*class))
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lines up to {} wide of {}",
+            self.lines, self.width, self.classes
+        )
+    }
+}
+
+const CLASSES: [&str; 6] = ["digit", "lower", "upper", "space", "punct", "other"];
+
+fn class_of(c: char) -> usize {
+    match c {
+        '0'..='9' => 0,
+        'a'..='z' => 1,
+        'A'..='Z' => 2,
+        ' ' | '\t' => 3,
+        c if c.is_ascii_punctuation() => 4,
+        _ => 5,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fingerprint {
+    /// SHA-256 of the input, in hex.
+    pub sha256: String,
+    pub shape: Shape,
+}
+
+impl Fingerprint {
+    /// Fingerprint of an already normalised input.
+    pub fn of(input: &str) -> Self {
+        Self {
+            sha256: Sha256::digest(input.as_bytes())
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+            shape: Shape::of(input),
+        }
+    }
+}
+
+/// The `fingerprints.tsv` of a day, by input name.
+#[derive(Debug)]
+pub struct Fingerprints {
+    path: PathBuf,
+    entries: BTreeMap<String, Fingerprint>,
+}
+
+impl Fingerprints {
+    /// Loads the fingerprints of `day_dir`; a missing file has none.
+    pub fn load(day_dir: &Path) -> Result<Self, AocError> {
+        let path = day_dir.join(FINGERPRINTS_FILE);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let entries = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                parse_entry(line).ok_or_else(|| AocError::InvalidFingerprints {
+                    path: path.display().to_string(),
+                    line: idx + 1,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { path, entries })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Fingerprint> {
+        self.entries.get(name)
+    }
+
+    /// Records the fingerprint of `input`, replacing any earlier one, and
+    /// rewrites the file.
+    pub fn record(&mut self, name: &str, input: &str) -> Result<(), AocError> {
+        self.entries.insert(name.to_string(), Fingerprint::of(input));
+        let contents = self
+            .entries
+            .iter()
+            .map(|(name, Fingerprint { sha256, shape })| {
+                format!(
+                    "{name}\t{sha256}\t{}\t{}\t{}\n",
+                    shape.lines, shape.width, shape.classes
+                )
+            })
+            .collect::<String>();
+        fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+fn parse_entry(line: &str) -> Option<(String, Fingerprint)> {
+    let mut fields = line.split('\t');
+    let name = fields.next()?.to_string();
+    let sha256 = fields.next()?.to_string();
+    let shape = Shape {
+        lines: fields.next()?.parse().ok()?,
+        width: fields.next()?.parse().ok()?,
+        classes: fields.next()?.to_string(),
+    };
+    fields
+        .next()
+        .is_none()
+        .then_some((name, Fingerprint { sha256, shape }))
+}
+
+/// Rejects an input at `path` that can't be a puzzle input: an empty one,
+/// a web page, or the site's message for a request without a session.
+pub fn check_plausible(path: &Path, input: &str) -> Result<(), AocError> {
+    let invalid = |reason: &str| AocError::InvalidInput {
+        path: path.display().to_string(),
+        reason: reason.to_string(),
+    };
+    let start = input
+        .trim_start()
+        .chars()
+        .take(100)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if input.trim().is_empty() {
+        Err(invalid("it is empty"))
+    } else if start.starts_with("<!doctype") || start.starts_with("<html") || input.contains("</html>") {
+        Err(invalid("it looks like an HTML page rather than a puzzle input"))
+    } else if start.starts_with("puzzle inputs differ by user") {
+        Err(invalid("it is the site's reply to a request without a valid session"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks the normalised `input` read from `name` in `day_dir` is
+/// plausible and matches its recorded fingerprint, if any.
+pub fn check(day_dir: &Path, name: &str, input: &str) -> Result<(), AocError> {
+    let path = day_dir.join(name);
+    check_plausible(&path, input)?;
+    let fingerprints = Fingerprints::load(day_dir)?;
+    let Some(recorded) = fingerprints.get(name) else {
+        return Ok(());
+    };
+    let actual = Fingerprint::of(input);
+    if actual.sha256 != recorded.sha256 {
+        return Err(AocError::InputChanged {
+            path: path.display().to_string(),
+            recorded: recorded.shape.to_string(),
+            actual: actual.shape.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn test_shape() {
+        assert_eq!(
+            Shape {
+                lines: 2,
+                width: 9,
+                classes: "digit,lower,upper,space,punct".to_string()
+            },
+            Shape::of("Game 1: x\n42")
+        );
+    }
+
+    #[rstest]
+    #[case::empty("\n")]
+    #[case::html("<!DOCTYPE html>\n<html lang=\"en-us\"></html>")]
+    #[case::error_page("<html><body>500 Internal Server Error</body></html>")]
+    #[case::logged_out("Puzzle inputs differ by user.  Please log in to get your puzzle input.")]
+    fn test_implausible(#[case] input: &str) {
+        assert!(matches!(
+            check_plausible(Path::new("input.txt"), input),
+            Err(AocError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_puzzle_input_is_plausible() -> miette::Result<()> {
+        check_plausible(Path::new("input.txt"), "<<>>\n<><>")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_and_check() -> miette::Result<()> {
+        let day = tempfile::tempdir().unwrap();
+        // Nothing recorded yet.
+        check(day.path(), "input.txt", "1 2\n3 4")?;
+
+        Fingerprints::load(day.path())?.record("input.txt", "1 2\n3 4")?;
+        let reloaded = Fingerprints::load(day.path())?;
+        assert_eq!(Some(&Fingerprint::of("1 2\n3 4")), reloaded.get("input.txt"));
+        check(day.path(), "input.txt", "1 2\n3 4")?;
+        check(day.path(), "input1.txt", "something else")?;
+
+        let error = check(day.path(), "input.txt", "1 2").unwrap_err();
+        assert_eq!(
+            "input.txt changed since it was recorded: it had 2 lines up to 3 wide of digit,space, now 1 lines up to 3 wide of digit,space",
+            error.to_string().replace(&format!("{}/", day.path().display()), "")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_fingerprints() {
+        let day = tempfile::tempdir().unwrap();
+        fs::write(day.path().join(FINGERPRINTS_FILE), "input.txt\tabc\tmany\t1\tdigit\n").unwrap();
+        assert!(matches!(
+            Fingerprints::load(day.path()),
+            Err(AocError::InvalidFingerprints { line: 1, .. })
+        ));
+    }
+}
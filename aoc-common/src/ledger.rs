@@ -0,0 +1,362 @@
+//! Local history of the answers submitted for a day, used to guard
+//! submissions before they reach the website.
+//!
+//! Every attempt is appended to `submissions.tsv` in the day's directory
+//! as `unix time, part, verdict, answer`, tab-separated. Before a new
+//! answer is sent, [`Ledger::check`] refuses it if the part is already
+//! solved, the answer was already judged, it falls outside the bounds the
+//! server's "too high"/"too low" verdicts have established, or the server
+//! asked us to wait and that time isn't up yet.
+
+use std::{
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::AocError;
+
+pub const LEDGER_FILE: &str = "submissions.tsv";
+
+/// The server's verdict on a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint which way.
+    Wrong,
+    /// Submitted too soon after a wrong answer; not judged.
+    Wait(Duration),
+    /// The part was already solved on the website; not judged.
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page the server returns for a
+    /// submission.
+    pub fn from_response(html: &str) -> Option<Self> {
+        if html.contains("That's the right answer") {
+            Some(Self::Correct)
+        } else if html.contains("your answer is too high") {
+            Some(Self::TooHigh)
+        } else if html.contains("your answer is too low") {
+            Some(Self::TooLow)
+        } else if html.contains("That's not the right answer") {
+            Some(Self::Wrong)
+        } else if html.contains("You gave an answer too recently") {
+            let left = html
+                .split_once("you have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map_or("60s", |(left, _)| left);
+            Some(Self::Wait(parse_wait(left)))
+        } else if html.contains("Did you already complete it") {
+            Some(Self::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the server actually judged the answer.
+    pub fn is_judged(&self) -> bool {
+        !matches!(self, Self::Wait(_) | Self::AlreadySolved)
+    }
+}
+
+/// Parses the server's `1m 20s` style durations.
+fn parse_wait(left: &str) -> Duration {
+    let seconds = left
+        .split_whitespace()
+        .filter_map(|part| {
+            let (value, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let value = value.parse::<u64>().ok()?;
+            match unit {
+                "h" => Some(value * 3600),
+                "m" => Some(value * 60),
+                "s" => Some(value),
+                _ => None,
+            }
+        })
+        .sum();
+    Duration::from_secs(seconds)
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => f.write_str("correct"),
+            Self::TooHigh => f.write_str("too-high"),
+            Self::TooLow => f.write_str("too-low"),
+            Self::Wrong => f.write_str("wrong"),
+            Self::Wait(left) => write!(f, "wait-{}", left.as_secs()),
+            Self::AlreadySolved => f.write_str("already-solved"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Self::Correct),
+            "too-high" => Ok(Self::TooHigh),
+            "too-low" => Ok(Self::TooLow),
+            "wrong" => Ok(Self::Wrong),
+            "already-solved" => Ok(Self::AlreadySolved),
+            _ => s
+                .strip_prefix("wait-")
+                .and_then(|seconds| seconds.parse().ok())
+                .map(|seconds| Self::Wait(Duration::from_secs(seconds)))
+                .ok_or(()),
+        }
+    }
+}
+
+/// One submission attempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+/// The submissions of one day, across both parts.
+#[derive(Debug)]
+pub struct Ledger {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    /// Loads the ledger in a day's directory; a missing file is an empty
+    /// ledger.
+    pub fn load(day_dir: &Path) -> Result<Self, AocError> {
+        let path = day_dir.join(LEDGER_FILE);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let attempts = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                parse_attempt(line).ok_or_else(|| AocError::InvalidLedger {
+                    path: path.display().to_string(),
+                    line: idx + 1,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { path, attempts })
+    }
+
+    pub fn attempts(&self, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempts.iter().filter(move |attempt| attempt.part == part)
+    }
+
+    /// The accepted answer to `part`, if any.
+    pub fn correct_answer(&self, part: u8) -> Option<&str> {
+        self.attempts(part)
+            .find(|attempt| attempt.verdict == Verdict::Correct)
+            .map(|attempt| attempt.answer.as_str())
+    }
+
+    /// Refuses `answer` to `part` if sending it at unix time `now` is
+    /// known to be pointless.
+    pub fn check(&self, part: u8, answer: &str, now: u64) -> Result<(), AocError> {
+        let refuse = |reason: String| {
+            Err(AocError::SubmissionRefused {
+                answer: answer.to_string(),
+                reason,
+            })
+        };
+
+        if answer.trim().is_empty() {
+            return refuse("the answer is empty".to_string());
+        }
+        if let Some(correct) = self.correct_answer(part) {
+            return refuse(format!("part {part} was already solved with {correct}"));
+        }
+        if let Some(previous) = self
+            .attempts(part)
+            .find(|attempt| attempt.answer == answer && attempt.verdict.is_judged())
+        {
+            return refuse(format!("it was already submitted and was {}", previous.verdict));
+        }
+        if let Some(until) = self.wait_until() {
+            if now < until {
+                return refuse(format!(
+                    "the server asked to wait another {}s",
+                    until - now
+                ));
+            }
+        }
+        if let Ok(value) = answer.parse::<i128>() {
+            let (low, high) = self.bounds(part);
+            if let Some(high) = high.filter(|high| value >= *high) {
+                return refuse(format!("{high} was already too high"));
+            }
+            if let Some(low) = low.filter(|low| value <= *low) {
+                return refuse(format!("{low} was already too low"));
+            }
+        }
+        Ok(())
+    }
+
+    /// The largest answer known to be too low and the smallest known to be
+    /// too high.
+    pub fn bounds(&self, part: u8) -> (Option<i128>, Option<i128>) {
+        let judged = |verdict| {
+            self.attempts(part)
+                .filter(move |attempt| attempt.verdict == verdict)
+                .filter_map(|attempt| attempt.answer.parse::<i128>().ok())
+        };
+        (judged(Verdict::TooLow).max(), judged(Verdict::TooHigh).min())
+    }
+
+    /// The cooldown applies to the whole account, so the latest wait of
+    /// either part counts.
+    fn wait_until(&self) -> Option<u64> {
+        self.attempts.iter().rev().find_map(|attempt| match attempt.verdict {
+            Verdict::Wait(left) => Some(attempt.at + left.as_secs()),
+            _ => None,
+        })
+    }
+
+    /// Appends an attempt to the ledger file.
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), AocError> {
+        use std::io::Write;
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}",
+            attempt.at, attempt.part, attempt.verdict, attempt.answer
+        )?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+fn parse_attempt(line: &str) -> Option<Attempt> {
+    let mut fields = line.splitn(4, '\t');
+    Some(Attempt {
+        at: fields.next()?.parse().ok()?,
+        part: fields.next()?.parse().ok()?,
+        verdict: fields.next()?.parse().ok()?,
+        answer: fields.next()?.to_string(),
+    })
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn ledger(attempts: &[(u64, u8, Verdict, &str)]) -> (tempfile::TempDir, Ledger) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ledger = Ledger::load(dir.path()).unwrap();
+        for &(at, part, verdict, answer) in attempts {
+            ledger
+                .record(Attempt {
+                    at,
+                    part,
+                    verdict,
+                    answer: answer.to_string(),
+                })
+                .unwrap();
+        }
+        (dir, ledger)
+    }
+
+    #[rstest]
+    #[case("<article><p>That's the right answer!  You are <em>one gold star</em> closer.</p></article>", Verdict::Correct)]
+    #[case("<article><p>That's not the right answer; your answer is too high.  If you're stuck...</p></article>", Verdict::TooHigh)]
+    #[case("<article><p>That's not the right answer; your answer is too low.</p></article>", Verdict::TooLow)]
+    #[case("<article><p>That's not the right answer.  If you're stuck...</p></article>", Verdict::Wrong)]
+    #[case("<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 34s left to wait.</p></article>", Verdict::Wait(Duration::from_secs(34)))]
+    #[case("<article><p>You gave an answer too recently; you have 1m 20s left to wait.</p></article>", Verdict::Wait(Duration::from_secs(80)))]
+    #[case("<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>", Verdict::AlreadySolved)]
+    fn test_verdict_from_response(#[case] html: &str, #[case] expected: Verdict) {
+        assert_eq!(Some(expected), Verdict::from_response(html));
+    }
+
+    #[test]
+    fn test_round_trips_through_file() {
+        let (dir, ledger) = ledger(&[
+            (100, 1, Verdict::TooHigh, "500"),
+            (200, 1, Verdict::Wait(Duration::from_secs(60)), "499"),
+            (300, 1, Verdict::Correct, "42"),
+            (400, 2, Verdict::Wrong, "a\tb"),
+        ]);
+        let reloaded = Ledger::load(dir.path()).unwrap();
+        assert_eq!(ledger.attempts, reloaded.attempts);
+        assert_eq!(Some("42"), reloaded.correct_answer(1));
+        assert_eq!(None, reloaded.correct_answer(2));
+    }
+
+    #[test]
+    fn test_corrupt_line_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(LEDGER_FILE), "100\t1\tcorrect\t42\nnonsense\n").unwrap();
+        assert!(matches!(
+            Ledger::load(dir.path()),
+            Err(AocError::InvalidLedger { line: 2, .. })
+        ));
+    }
+
+    #[rstest]
+    #[case::fresh("150", true)]
+    #[case::duplicate("200", false)]
+    #[case::above_too_high("300", false)]
+    #[case::at_too_high("250", false)]
+    #[case::below_too_low("90", false)]
+    #[case::at_too_low("100", false)]
+    #[case::not_a_number("abc", true)]
+    #[case::empty(" ", false)]
+    fn test_check(#[case] answer: &str, #[case] allowed: bool) {
+        let (_dir, ledger) = ledger(&[
+            (0, 1, Verdict::TooLow, "100"),
+            (0, 1, Verdict::TooHigh, "250"),
+            (0, 1, Verdict::Wrong, "200"),
+            (0, 1, Verdict::TooHigh, "400"),
+            (0, 2, Verdict::TooHigh, "150"),
+        ]);
+        assert_eq!(allowed, ledger.check(1, answer, 1_000).is_ok(), "{answer}");
+    }
+
+    #[test]
+    fn test_solved_part_is_refused() {
+        let (_dir, ledger) = ledger(&[(0, 1, Verdict::Correct, "42")]);
+        assert!(matches!(
+            ledger.check(1, "43", 0),
+            Err(AocError::SubmissionRefused { .. })
+        ));
+        assert!(ledger.check(2, "43", 0).is_ok());
+    }
+
+    #[test]
+    fn test_wait_is_honoured_then_answer_may_be_resent() {
+        let (_dir, ledger) =
+            ledger(&[(1_000, 2, Verdict::Wait(Duration::from_secs(30)), "7")]);
+        assert!(ledger.check(1, "8", 1_010).is_err());
+        assert!(ledger.check(2, "7", 1_029).is_err());
+        assert!(ledger.check(2, "7", 1_030).is_ok());
+    }
+}
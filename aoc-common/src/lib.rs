@@ -0,0 +1,27 @@
+pub mod allocations;
+pub mod answers;
+pub mod bench_harness;
+pub mod bench_history;
+pub mod budgets;
+pub mod cancel;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "dashboard")]
+pub mod dashboard;
+pub mod error;
+pub mod input;
+pub mod integrity;
+pub mod input_sets;
+pub mod ledger;
+pub mod output;
+pub mod progress;
+pub mod puzzle;
+pub mod runner;
+pub mod scan;
+pub mod solutions;
+pub mod vault;
+pub mod verify;
+#[cfg(feature = "year")]
+pub mod year;
+
+pub use error::AocError;
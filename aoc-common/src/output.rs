@@ -0,0 +1,456 @@
+//! Machine-readable results, for dashboards and spreadsheets. The part
+//! bins print the bare answer unless `AOC_OUTPUT` asks for a record of the
+//! run instead: `json` prints it as a line of JSON, `csv` as a row under a
+//! header. `just run-year` prints the records of all its runs the same way,
+//! in place of its table.
+//!
+//! A record has the year, day, part and variant, the answer, the times of
+//! the parse and solve steps in nanoseconds, the SHA-256 of the input (as in
+//! `fingerprints.tsv`) and the error the run failed with, rendered as text
+//! by miette. Variants registered without [stages](crate::solutions::Stages)
+//! have no parse step; all of their run counts as solving.
+
+use std::{
+    fmt::Write,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use miette::{Context, NarratableReportHandler};
+
+use crate::{
+    answers, input,
+    integrity::Fingerprint,
+    progress,
+    runner::Runner,
+    solutions::{Stages, Variant},
+    verify, AocError,
+};
+
+/// Environment variable selecting the [`Format`] of the bins.
+pub const OUTPUT_ENV: &str = "AOC_OUTPUT";
+
+pub const CSV_HEADER: &str = "year,day,part,variant,answer,parse_ns,solve_ns,input_hash,error";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The bare answer, or the table of `just run-year`.
+    Text,
+    JsonLines,
+    Csv,
+}
+
+impl Format {
+    /// Format from [`OUTPUT_ENV`], text if unset.
+    pub fn from_env() -> Result<Self, AocError> {
+        match std::env::var(OUTPUT_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::Text),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = AocError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "" | "text" => Ok(Self::Text),
+            "json" | "jsonl" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
+            _ => Err(AocError::InvalidOutputFormat {
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+/// Times of the steps of a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    /// Building the model, for variants registered with stages.
+    pub parse: Option<Duration>,
+    pub solve: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse.unwrap_or_default() + self.solve
+    }
+}
+
+/// Runs `variant` on `input` through `runner`, timing its parse and solve
+/// steps apart if it has stages, and turning a panic into
+/// [`AocError::Panicked`]. Only the solution is timed, not the runner's
+/// watchdog.
+pub fn run(runner: &Runner, input: &str, variant: &Variant) -> Result<(String, Timings), AocError> {
+    let (process, stages) = (variant.process, variant.stages);
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        runner.run_with(input, move |input| match stages {
+            Some(stages) => run_stages(input, stages),
+            None => {
+                let start = Instant::now();
+                let answer = process(input)?;
+                let solve = start.elapsed();
+                Ok((answer, Timings { parse: None, solve }))
+            }
+        })
+    }))
+    .unwrap_or_else(|panic| {
+        Err(AocError::Panicked {
+            message: verify::panic_message(&*panic).unwrap_or_else(|| "no message".to_string()),
+        })
+    })
+}
+
+fn run_stages(input: &str, stages: Stages) -> Result<(String, Timings), AocError> {
+    let start = Instant::now();
+    let mut result = None;
+    (stages.with_solver)(input, &mut |solve| {
+        let parse = start.elapsed();
+        let start = Instant::now();
+        result = Some(solve().map(|answer| {
+            let solve = start.elapsed();
+            (answer, Timings { parse: Some(parse), solve })
+        }));
+    })?;
+    result.expect("the solve step is handed over once the input is parsed")
+}
+
+/// Renders an error as plain text, with its diagnostic code and help.
+pub fn render_error(error: &AocError) -> String {
+    let mut text = String::new();
+    NarratableReportHandler::new()
+        .render_report(&mut text, error)
+        .expect("writing to a String can't fail");
+    text.trim_end().to_string()
+}
+
+/// One run of a variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// From the name of the year's directory, e.g. `2023`.
+    pub year: Option<u16>,
+    pub day: u8,
+    pub part: u8,
+    pub variant: &'static str,
+    pub answer: Option<String>,
+    pub timings: Option<Timings>,
+    /// SHA-256 of the input, in hex, if it could be read.
+    pub input_hash: Option<String>,
+    /// The error the run failed with, from [`render_error`].
+    pub error: Option<String>,
+}
+
+impl Record {
+    /// Record of `variant` of the day in `day_dir`, with the result of
+    /// reading the input and running it.
+    pub fn new(
+        day_dir: &Path,
+        variant: &Variant,
+        input_hash: Option<String>,
+        result: Result<&(String, Timings), &AocError>,
+    ) -> Self {
+        let year = day_dir
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str()?.parse().ok());
+        let (answer, timings, error) = match result {
+            Ok((answer, timings)) => (Some(answer.clone()), Some(*timings), None),
+            Err(error) => (None, None, Some(render_error(error))),
+        };
+        Self {
+            year,
+            day: answers::day_of(day_dir).unwrap_or_default(),
+            part: variant.part,
+            variant: variant.name,
+            answer,
+            timings,
+            input_hash,
+            error,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let string = |value: Option<&str>| value.map_or_else(|| "null".to_string(), json_string);
+        let number = |value: Option<u128>| value.map_or_else(|| "null".to_string(), |value| value.to_string());
+        let (parse, solve) = self.nanos();
+        format!(
+            r#"{{"year":{},"day":{},"part":{},"variant":{},"answer":{},"parse_ns":{},"solve_ns":{},"input_hash":{},"error":{}}}"#,
+            number(self.year.map(u128::from)),
+            self.day,
+            self.part,
+            json_string(self.variant),
+            string(self.answer.as_deref()),
+            number(parse),
+            number(solve),
+            string(self.input_hash.as_deref()),
+            string(self.error.as_deref()),
+        )
+    }
+
+    /// The record as a row under [`CSV_HEADER`], empty fields for what it
+    /// doesn't have.
+    pub fn to_csv(&self) -> String {
+        let number = |value: Option<u128>| value.map(|value| value.to_string()).unwrap_or_default();
+        let (parse, solve) = self.nanos();
+        [
+            number(self.year.map(u128::from)),
+            self.day.to_string(),
+            self.part.to_string(),
+            csv_field(self.variant),
+            csv_field(self.answer.as_deref().unwrap_or_default()),
+            number(parse),
+            number(solve),
+            self.input_hash.clone().unwrap_or_default(),
+            csv_field(self.error.as_deref().unwrap_or_default()),
+        ]
+        .join(",")
+    }
+
+    fn nanos(&self) -> (Option<u128>, Option<u128>) {
+        let parse = self.timings.and_then(|timings| timings.parse).map(|parse| parse.as_nanos());
+        let solve = self.timings.map(|timings| timings.solve.as_nanos());
+        (parse, solve)
+    }
+}
+
+/// The records in `format`, a line each, after the header for CSV.
+/// Text is one answer, or error, per line.
+pub fn format(format: Format, records: &[Record]) -> String {
+    let mut output = String::new();
+    if format == Format::Csv {
+        output.push_str(CSV_HEADER);
+        output.push('\n');
+    }
+    for record in records {
+        let line = match format {
+            Format::Text => record
+                .answer
+                .clone()
+                .or_else(|| record.error.clone())
+                .unwrap_or_default(),
+            Format::JsonLines => record.to_json(),
+            Format::Csv => record.to_csv(),
+        };
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Entry point of the part bins, through [`part_main!`](crate::part_main):
+/// prints the answer, or the record of the run in the format from
+/// [`OUTPUT_ENV`]. A failed run still prints its record, then fails.
+pub fn main(day_dir: &str, variant: Variant) -> miette::Result<()> {
+    progress::enable();
+    let day_dir = Path::new(day_dir);
+    let runner = Runner::from_env()?;
+    let output = Format::from_env()?;
+    if output == Format::Text {
+        let input = input::read(day_dir, variant.part).context("read input")?;
+        let answer = runner
+            .run(&input, variant.process)
+            .with_context(|| format!("process {variant}"))?;
+        println!("{answer}");
+        return Ok(());
+    }
+
+    let (input_hash, result) = match input::read(day_dir, variant.part) {
+        Ok(input) => (
+            Some(Fingerprint::of(&input).sha256),
+            run(&runner, &input, &variant),
+        ),
+        Err(error) => (None, Err(error)),
+    };
+    let record = Record::new(day_dir, &variant, input_hash, result.as_ref());
+    print!("{}", format(output, &[record]));
+    if result.is_err() {
+        return Err(AocError::VerificationFailed { failed: 1, total: 1 }.into());
+    }
+    Ok(())
+}
+
+/// The `main` of a day's part bin, e.g. `src/bin/part1_nom.rs`:
+///
+/// ```ignore
+/// use day_1::part1_nom::process;
+///
+/// fn main() -> miette::Result<()> {
+///     aoc_common::part_main!(day_1, 1, process)
+/// }
+/// ```
+///
+/// The bin is named after its variant, whose stages, if any, come from the
+/// day's `SOLUTIONS`.
+#[macro_export]
+macro_rules! part_main {
+    ($day:ident, $part:literal, $process:path) => {
+        $crate::output::main(
+            env!("CARGO_MANIFEST_DIR"),
+            $crate::solutions::Variant {
+                part: $part,
+                name: env!("CARGO_BIN_NAME"),
+                process: $process,
+                slow: false,
+                stages: $day::SOLUTIONS
+                    .iter()
+                    .find(|variant| variant.name == env!("CARGO_BIN_NAME"))
+                    .and_then(|variant| variant.stages),
+            },
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn length(input: &str) -> Result<String, AocError> {
+        Ok(input.len().to_string())
+    }
+
+    fn unfinished(_input: &str) -> Result<String, AocError> {
+        todo!("part 2")
+    }
+
+    fn parse(input: &str) -> Result<Vec<&str>, AocError> {
+        Ok(input.split_whitespace().collect())
+    }
+
+    fn solve(words: &[&str]) -> Result<String, AocError> {
+        Ok(words.len().to_string())
+    }
+
+    fn variant(process: crate::runner::Solution) -> Variant {
+        Variant {
+            part: 1,
+            name: "part1",
+            process,
+            slow: false,
+            stages: None,
+        }
+    }
+
+    fn record(result: Result<&(String, Timings), &AocError>) -> Record {
+        Record::new(
+            Path::new("/aoc/2023/day-4"),
+            &variant(length),
+            Some("ab12".to_string()),
+            result,
+        )
+    }
+
+    #[test]
+    fn test_run() -> miette::Result<()> {
+        let (answer, timings) = run(&Runner::new(), "a b c", &variant(length))?;
+        assert_eq!("5", answer);
+        assert_eq!(None, timings.parse);
+
+        let staged = Variant {
+            stages: crate::__stages!({ parse: parse, solve: solve }),
+            ..variant(length)
+        };
+        let (answer, timings) = run(&Runner::new(), "a b c", &staged)?;
+        assert_eq!("3", answer);
+        assert!(timings.parse.is_some());
+        assert_eq!(timings.total(), timings.parse.unwrap() + timings.solve);
+
+        let error = run(&Runner::new(), "", &variant(unfinished)).unwrap_err();
+        assert_eq!(
+            "solution panicked: not yet implemented: part 2",
+            error.to_string()
+        );
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("json", Format::JsonLines)]
+    #[case("csv", Format::Csv)]
+    #[case("", Format::Text)]
+    fn test_format(#[case] value: &str, #[case] expected: Format) -> miette::Result<()> {
+        assert_eq!(expected, value.parse()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        assert!(matches!(
+            "yaml".parse::<Format>(),
+            Err(AocError::InvalidOutputFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_records() {
+        let timings = Timings {
+            parse: Some(Duration::from_nanos(1500)),
+            solve: Duration::from_micros(20),
+        };
+        let passed = record(Ok(&("13".to_string(), timings)));
+        assert_eq!(Some(2023), passed.year);
+        assert_eq!(4, passed.day);
+        assert_eq!(
+            r#"{"year":2023,"day":4,"part":1,"variant":"part1","answer":"13","parse_ns":1500,"solve_ns":20000,"input_hash":"ab12","error":null}"#,
+            passed.to_json()
+        );
+        assert_eq!("2023,4,1,part1,13,1500,20000,ab12,", passed.to_csv());
+
+        let failed = record(Err(&AocError::Panicked {
+            message: "index 3, \"out\" of bounds".to_string(),
+        }));
+        let error = failed.error.clone().unwrap();
+        assert!(error.starts_with("solution panicked: index 3, \"out\" of bounds\n"), "{error}");
+        assert!(error.contains("aoc::panicked"), "{error}");
+        assert!(
+            failed.to_json().ends_with(&format!(
+                r#""answer":null,"parse_ns":null,"solve_ns":null,"input_hash":"ab12","error":{}}}"#,
+                json_string(&error)
+            )),
+            "{}",
+            failed.to_json()
+        );
+        assert!(failed.to_csv().starts_with("2023,4,1,part1,,,,ab12,\"solution panicked: index 3, \"\"out\"\" of bounds\n"));
+
+        let csv = format(Format::Csv, &[passed.clone(), failed]);
+        assert_eq!(Some(CSV_HEADER), csv.lines().next());
+        assert_eq!("13\n", format(Format::Text, &[passed]));
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(r#""a\"b\\c\nd\u0001""#, json_string("a\"b\\c\nd\u{1}"));
+    }
+}
@@ -0,0 +1,166 @@
+//! Progress reporting for long-running solutions.
+//!
+//! A solution announces how many units of work it has with
+//! [`Progress::new`] and reports them with [`Progress::inc`]. Progress is
+//! only tracked when someone is watching: a bar is drawn when the binary
+//! running it called [`enable`] and stderr is a terminal, and a runner with
+//! a time budget records it to report how far a timed-out run got. Tests
+//! and benches pay for a single `None` check per call.
+
+use std::{
+    io::IsTerminal,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::runner;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+const TEMPLATE: &str =
+    "{spinner} [{elapsed_precise}] {wide_bar} {human_pos}/{human_len} ({eta})";
+
+/// Turns on progress bars for this process if stderr is a terminal.
+/// Returns whether bars will be drawn.
+pub fn enable() -> bool {
+    let attached = std::io::stderr().is_terminal();
+    ENABLED.store(attached, Ordering::Relaxed);
+    attached
+}
+
+/// Turns progress bars off again, e.g. before timing a run.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+#[derive(Debug)]
+pub(crate) struct Tracker {
+    done: AtomicU64,
+    total: u64,
+    bar: Option<ProgressBar>,
+}
+
+impl Tracker {
+    pub(crate) fn position(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+impl Drop for Tracker {
+    /// An unfinished bar would stay drawn on the terminal.
+    fn drop(&mut self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// Handle a solution reports its work through. Cloning it is cheap and
+/// clones report into the same bar, so it can be shared with rayon; the
+/// bar is cleared once the last clone, and the budgeted run's record of
+/// it, are dropped.
+#[derive(Clone, Debug)]
+pub struct Progress {
+    tracker: Option<Arc<Tracker>>,
+}
+
+impl Progress {
+    /// Starts tracking `total` units of work. Call it on the thread
+    /// `process` was called on, so a budgeted run can see it.
+    pub fn new(total: u64) -> Self {
+        let bar = ENABLED.load(Ordering::Relaxed).then(|| {
+            ProgressBar::new(total).with_style(
+                ProgressStyle::with_template(TEMPLATE)
+                    .expect("progress template is valid"),
+            )
+        });
+        runner::with_context(|context| {
+            if bar.is_none() && context.is_none() {
+                return Self { tracker: None };
+            }
+            let tracker = Arc::new(Tracker {
+                done: AtomicU64::new(0),
+                total,
+                bar,
+            });
+            if let Some(context) = context {
+                context.track(&tracker);
+            }
+            Self {
+                tracker: Some(tracker),
+            }
+        })
+    }
+
+    /// Whether anything is watching this progress.
+    pub fn is_observed(&self) -> bool {
+        self.tracker.is_some()
+    }
+
+    /// Records `delta` more units of work as done.
+    #[inline]
+    pub fn inc(&self, delta: u64) {
+        if let Some(tracker) = &self.tracker {
+            tracker.done.fetch_add(delta, Ordering::Relaxed);
+            if let Some(bar) = &tracker.bar {
+                bar.inc(delta);
+            }
+        }
+    }
+
+    /// Units of work done so far, or zero when unobserved.
+    pub fn position(&self) -> u64 {
+        self.tracker.as_ref().map_or(0, |tracker| tracker.position())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // One test owns the global switch, so parallel tests can't race on it.
+    #[test]
+    fn test_progress_only_observed_when_enabled() {
+        let silent = Progress::new(10);
+        silent.inc(4);
+        assert!(!silent.is_observed());
+        assert_eq!(0, silent.position());
+
+        ENABLED.store(true, Ordering::Relaxed);
+        let observed = Progress::new(10);
+        let shared = observed.clone();
+        observed.inc(4);
+        shared.inc(3);
+        disable();
+
+        assert!(observed.is_observed());
+        assert_eq!(7, observed.position());
+        assert!(!Progress::new(10).is_observed());
+    }
+
+    #[test]
+    fn test_bar_cleared_on_drop() {
+        let bar = ProgressBar::hidden();
+        let progress = Progress {
+            tracker: Some(Arc::new(Tracker {
+                done: AtomicU64::new(0),
+                total: 10,
+                bar: Some(bar.clone()),
+            })),
+        };
+        let shared = progress.clone();
+        drop(progress);
+        assert!(!bar.is_finished());
+        drop(shared);
+        assert!(bar.is_finished());
+    }
+}
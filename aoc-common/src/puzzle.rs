@@ -0,0 +1,497 @@
+//! Reads the `puzzle.md` stored in each day's directory (as written by
+//! `client::puzzle_to_markdown`) into a [`Puzzle`]: its title, and per part
+//! the description, fenced example blocks and the answers emphasised in
+//! the text.
+//!
+//! [`example_tests!`](crate::example_tests) turns those into tests, so a
+//! day checks every variant against the puzzle's own examples.
+
+use std::{fs, path::Path};
+
+use crate::{input::normalize, runner::Solution, AocError};
+
+pub const PUZZLE_FILE: &str = "puzzle.md";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Puzzle {
+    pub day: u8,
+    pub title: String,
+    /// Part one, then part two once it has been unlocked.
+    pub parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Part {
+    /// 1 or 2.
+    pub number: u8,
+    /// The description, without the answer form and links that follow it.
+    pub text: String,
+    /// Contents of every fenced block, without trailing newlines.
+    pub examples: Vec<String>,
+    /// Every `` `*value*` `` in the description, in order. Intermediate
+    /// results are emphasised too; the example's answer is usually last.
+    pub emphasised: Vec<String>,
+    /// The accepted answer (`Your puzzle answer was ...`), once solved.
+    pub answer: Option<String>,
+}
+
+impl Part {
+    /// The example the puzzle is worked through on. Days that show a
+    /// fragment before the full example (2023 day 5) have it as the
+    /// longest block.
+    pub fn example(&self) -> Option<&str> {
+        self.examples
+            .iter()
+            .rev()
+            .max_by_key(|example| example.len())
+            .map(String::as_str)
+    }
+
+    /// The answer to [`Part::example`], by convention the last value
+    /// emphasised in the description.
+    pub fn example_answer(&self) -> Option<&str> {
+        self.emphasised.last().map(String::as_str)
+    }
+}
+
+impl Puzzle {
+    pub fn load(day_dir: &Path) -> Result<Self, AocError> {
+        let path = day_dir.join(PUZZLE_FILE);
+        Self::parse(&fs::read_to_string(&path)?).map_err(|error| match error {
+            AocError::InvalidPuzzle { reason, .. } => AocError::InvalidPuzzle {
+                path: path.display().to_string(),
+                reason,
+            },
+            error => error,
+        })
+    }
+
+    pub fn parse(markdown: &str) -> Result<Self, AocError> {
+        let invalid = |reason: &str| AocError::InvalidPuzzle {
+            path: PUZZLE_FILE.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let mut sections = split_sections(markdown).into_iter();
+        let (heading, body) = sections
+            .next()
+            .ok_or_else(|| invalid("no `--- Day N: Title ---` heading"))?;
+        let (day, title) = heading
+            .strip_prefix("Day ")
+            .and_then(|rest| rest.split_once(": "))
+            .and_then(|(day, title)| Some((day.parse().ok()?, title.to_string())))
+            .ok_or_else(|| invalid("the first heading is not `--- Day N: Title ---`"))?;
+
+        let mut parts = vec![parse_part(1, body)];
+        for (heading, body) in sections {
+            if heading != "Part Two" {
+                return Err(invalid("unexpected heading after part one"));
+            }
+            parts.push(parse_part(2, body));
+        }
+
+        Ok(Self { day, title, parts })
+    }
+
+    pub fn part(&self, number: u8) -> Option<&Part> {
+        self.parts.iter().find(|part| part.number == number)
+    }
+}
+
+/// Runs `process` on the example of `part` in the `puzzle.md` in
+/// `day_dir` and checks it returns the answer emphasised in the text, both
+/// as written and as a file saved with a BOM, CRLF line endings or a
+/// trailing newline would have it once [normalised](normalize). A wrong
+/// answer is an [`AocError::WrongExampleAnswer`] naming the way the
+/// example was written. Parts that haven't been unlocked yet pass, with a
+/// note on stderr.
+pub fn check_example(day_dir: &Path, part: u8, process: Solution) -> Result<(), AocError> {
+    let puzzle = Puzzle::load(day_dir)?;
+    let Some(described) = puzzle.part(part) else {
+        eprintln!(
+            "part {part} isn't in {}/{PUZZLE_FILE} yet, skipping its example",
+            day_dir.display()
+        );
+        return Ok(());
+    };
+    let invalid = |reason: String| AocError::InvalidPuzzle {
+        path: day_dir.join(PUZZLE_FILE).display().to_string(),
+        reason,
+    };
+    // Part two often reuses the example of part one without repeating it.
+    let example = described
+        .example()
+        .or_else(|| puzzle.part(1)?.example())
+        .ok_or_else(|| invalid(format!("part {part} has no example block")))?;
+    let expected = described
+        .example_answer()
+        .ok_or_else(|| invalid(format!("part {part} has no emphasised answer")))?;
+
+    let crlf = example.replace('\n', "\r\n");
+    let variants = [
+        ("as written", example.to_string()),
+        ("with CRLF line endings", crlf.clone()),
+        ("with a trailing newline", format!("{example}\n")),
+        ("with a BOM, CRLF and a trailing blank line", format!("\u{feff}{crlf}\r\n\r\n")),
+    ];
+    for (variant, text) in variants {
+        let actual = process(&normalize(&text))?;
+        if actual != expected {
+            return Err(AocError::WrongExampleAnswer {
+                part,
+                variant: variant.to_string(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Declares a test per variant that checks it against its part's example
+/// in the crate's `puzzle.md`. Days get these through
+/// [`solutions!`](crate::solutions) rather than invoking it directly.
+#[macro_export]
+macro_rules! example_tests {
+    ($($(#[$attr:meta])* $name:ident: $part:literal => $process:path),* $(,)?) => {
+        #[cfg(test)]
+        mod example_tests {
+            $(
+                $(#[$attr])*
+                #[test]
+                fn $name() -> Result<(), $crate::AocError> {
+                    $crate::puzzle::check_example(
+                        ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+                        $part,
+                        $process,
+                    )
+                }
+            )*
+        }
+    };
+}
+
+/// Splits the file at its `--- Heading ---` lines, each underlined with
+/// dashes, into `(heading, body)` pairs.
+fn split_sections(markdown: &str) -> Vec<(&str, &str)> {
+    let mut sections: Vec<(&str, usize)> = Vec::new();
+    let mut bodies = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+    let mut lines = markdown.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        let start = offset;
+        offset += line.len();
+        if line.starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let heading = line
+            .trim_end()
+            .trim_start_matches('\\')
+            .strip_prefix("--- ")
+            .and_then(|rest| rest.strip_suffix(" ---"));
+        let underlined = lines
+            .peek()
+            .is_some_and(|next| next.trim_end().starts_with("---"));
+        if let (false, Some(heading), true) = (in_fence, heading, underlined) {
+            if let Some(&(_, body_start)) = sections.last() {
+                bodies.push(&markdown[body_start..start]);
+            }
+            offset += lines.next().map_or(0, str::len);
+            sections.push((heading, offset));
+        }
+    }
+    if let Some(&(_, body_start)) = sections.last() {
+        bodies.push(&markdown[body_start..]);
+    }
+    sections
+        .into_iter()
+        .zip(bodies)
+        .map(|((heading, _), body)| (heading, body))
+        .collect()
+}
+
+/// Lines that start the answer form and links below a part's description.
+const TRAILERS: [&str; 5] = [
+    "Your puzzle answer was",
+    "Answer:",
+    "Both parts of this puzzle are complete",
+    "The first half of this puzzle is complete",
+    "To begin, ",
+];
+
+fn parse_part(number: u8, body: &str) -> Part {
+    let answer = body.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("Your puzzle answer was `")
+            .and_then(|rest| rest.split_once('`'))
+            .map(|(answer, _)| answer.to_string())
+    });
+
+    let mut text = String::new();
+    let mut examples = Vec::new();
+    let mut fence: Option<String> = None;
+    for line in body.split_inclusive('\n') {
+        match fence.as_mut() {
+            Some(example) if line.starts_with("```") => {
+                examples.push(example.trim_end_matches('\n').to_string());
+                fence = None;
+            }
+            Some(example) => example.push_str(line),
+            None if line.starts_with("```") => fence = Some(String::new()),
+            None if TRAILERS.iter().any(|trailer| line.starts_with(trailer)) => break,
+            None => {}
+        }
+        text.push_str(line);
+    }
+
+    Part {
+        number,
+        emphasised: emphasised(&text),
+        text: text.trim().to_string(),
+        examples,
+        answer,
+    }
+}
+
+/// Values written as `` `*value*` ``, outside fenced blocks.
+fn emphasised(text: &str) -> Vec<String> {
+    let mut in_fence = false;
+    text.lines()
+        .filter(|line| {
+            if line.starts_with("```") {
+                in_fence = !in_fence;
+                return false;
+            }
+            !in_fence
+        })
+        .flat_map(|line| line.split("`*").skip(1))
+        .filter_map(|rest| rest.split_once("*`"))
+        .map(|(value, _)| value.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    /// Both parts solved, each with its own example.
+    const SOLVED: &str = r"\--- Day 1: Pairs ---
+----------
+
+Each line of the list holds two numbers. For example:
+
+```
+1 2
+3 4
+```
+
+The lines add up to `3` and `7`, for a total of `*10*`. *What is the total of the list?*
+
+Your puzzle answer was `1234`.
+
+\--- Part Two ---
+----------
+
+Multiply the numbers of each line instead. For example:
+
+```
+5 6
+```
+
+This gives `*30*`. *What is the new total?*
+
+Your puzzle answer was `5678`.
+
+Both parts of this puzzle are complete! They provide two gold stars: \*\*
+
+If you still want to see it, you can [get your puzzle input](1/input).
+";
+
+    /// Part one solved; part two reuses its example and awaits an answer.
+    const HALF_SOLVED: &str = r"\--- Day 2: Letters ---
+----------
+
+Count the letters. For example:
+
+```
+abc
+
+```
+
+There are `*3*`.
+
+Your puzzle answer was `42`.
+
+The first half of this puzzle is complete! It provides one gold star: \*
+
+\--- Part Two ---
+----------
+
+Count each letter twice in the same example, which gives `*6*`.
+
+Answer:
+
+Although it hasn't changed, you can still [get your puzzle input](2/input).
+";
+
+    /// Only part one, unsolved, showing a fragment before the example.
+    const LOCKED: &str = r"\--- Day 3: Fragments ---
+----------
+
+Lines look like this:
+
+```
+x
+```
+
+A full example:
+
+```
+x y
+x z
+```
+
+It has `*2*` lines.
+
+To begin, [get your puzzle input](3/input).
+
+Answer:
+";
+
+    fn day_with(markdown: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(PUZZLE_FILE), markdown).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_solved_day() -> miette::Result<()> {
+        let puzzle = Puzzle::parse(SOLVED)?;
+        assert_eq!(1, puzzle.day);
+        assert_eq!("Pairs", puzzle.title);
+        assert_eq!(2, puzzle.parts.len());
+
+        let part1 = puzzle.part(1).unwrap();
+        assert_eq!(Some("1234"), part1.answer.as_deref());
+        assert_eq!(Some("1 2\n3 4"), part1.example());
+        assert_eq!(vec!["10"], part1.emphasised);
+        assert!(part1.text.ends_with("*What is the total of the list?*"));
+
+        let part2 = puzzle.part(2).unwrap();
+        assert_eq!(Some("5678"), part2.answer.as_deref());
+        assert_eq!(Some("5 6"), part2.example());
+        assert_eq!(Some("30"), part2.example_answer());
+        assert!(!part2.text.contains("gold stars"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_half_solved_day() -> miette::Result<()> {
+        let puzzle = Puzzle::parse(HALF_SOLVED)?;
+        assert_eq!((2, "Letters"), (puzzle.day, puzzle.title.as_str()));
+        assert_eq!(Some("42"), puzzle.parts[0].answer.as_deref());
+        assert_eq!(Some("abc"), puzzle.parts[0].example());
+        assert_eq!(None, puzzle.parts[1].answer);
+        assert_eq!(None, puzzle.parts[1].example());
+        assert_eq!(Some("6"), puzzle.parts[1].example_answer());
+        assert!(!puzzle.parts[1].text.contains("Answer:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_longest_block_is_the_example() -> miette::Result<()> {
+        let puzzle = Puzzle::parse(LOCKED)?;
+        let part1 = &puzzle.parts[0];
+        assert_eq!(2, part1.examples.len());
+        assert_eq!(Some("x y\nx z"), part1.example());
+        assert_eq!(Some("2"), part1.example_answer());
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_one_only() -> miette::Result<()> {
+        let puzzle = Puzzle::parse(LOCKED)?;
+        assert_eq!(1, puzzle.parts.len());
+        assert_eq!(None, puzzle.parts[0].answer);
+        assert_eq!(None, puzzle.part(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fenced_heading_is_not_a_section() -> miette::Result<()> {
+        let markdown = "\\--- Day 9: Test ---\n----------\n\n```\n--- Part Two ---\n----------\n```\n\nIt is `*7*`.\n";
+        let puzzle = Puzzle::parse(markdown)?;
+        assert_eq!(1, puzzle.parts.len());
+        assert_eq!(vec!["--- Part Two ---\n----------"], puzzle.parts[0].examples);
+        assert_eq!(Some("7"), puzzle.parts[0].example_answer());
+        Ok(())
+    }
+
+    fn letters(input: &str) -> Result<String, AocError> {
+        Ok(input.chars().filter(char::is_ascii_alphabetic).count().to_string())
+    }
+
+    fn twice_the_letters(input: &str) -> Result<String, AocError> {
+        Ok((2 * letters(input)?.parse::<usize>().unwrap()).to_string())
+    }
+
+    /// Like a day that splits on `'\n'` and parses every line.
+    fn strict(input: &str) -> Result<String, AocError> {
+        for line in input.split('\n') {
+            assert!(line.starts_with(char::is_alphanumeric), "{line:?}");
+            assert!(!line.ends_with('\r'), "{line:?}");
+        }
+        Ok("10".to_string())
+    }
+
+    fn wrong(_input: &str) -> Result<String, AocError> {
+        Ok("0".to_string())
+    }
+
+    #[test]
+    fn test_check_example() -> miette::Result<()> {
+        let day = day_with(HALF_SOLVED);
+        check_example(day.path(), 1, letters)?;
+        // Part two falls back to the example of part one.
+        check_example(day.path(), 2, twice_the_letters)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_example_feeds_normalised_variants() -> miette::Result<()> {
+        let day = day_with(SOLVED);
+        check_example(day.path(), 1, strict)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_example_fails_on_wrong_answer() {
+        let day = day_with(SOLVED);
+        let error = check_example(day.path(), 1, wrong).unwrap_err();
+        assert!(
+            matches!(&error, AocError::WrongExampleAnswer { part: 1, variant, .. } if variant == "as written"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn test_check_example_skips_locked_part() -> miette::Result<()> {
+        let day = day_with(LOCKED);
+        check_example(day.path(), 2, wrong)?;
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("Just some text\n")]
+    #[case("\\--- Not a day ---\n----------\n")]
+    fn test_invalid(#[case] markdown: &str) {
+        assert!(matches!(
+            Puzzle::parse(markdown),
+            Err(AocError::InvalidPuzzle { .. })
+        ));
+    }
+}
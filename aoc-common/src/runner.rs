@@ -0,0 +1,281 @@
+//! Runs a solution's `process` on an input, optionally under a time budget.
+//!
+//! Without a budget the solution is simply called. With one it runs on its
+//! own thread; once the budget is spent its [`CancellationToken`] is
+//! cancelled and the solution gets a grace period to notice. Either way the
+//! runner stops waiting and returns [`AocError::TimedOut`], so a batch of
+//! runs can't hang on one solution.
+
+use std::{
+    cell::RefCell,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    cancel::CancellationToken,
+    progress::{self, Tracker},
+    AocError,
+};
+
+/// Signature of every day's `partN::process`.
+pub type Solution = fn(&str) -> Result<String, AocError>;
+
+/// Environment variable holding the time budget, in seconds, for the bins.
+pub const BUDGET_ENV: &str = "AOC_TIME_BUDGET";
+
+/// State of the budgeted run on the current thread.
+pub(crate) struct RunContext {
+    pub(crate) token: CancellationToken,
+    /// Progress most recently started by the solution.
+    progress: Mutex<Option<Arc<Tracker>>>,
+}
+
+impl RunContext {
+    pub(crate) fn track(&self, tracker: &Arc<Tracker>) {
+        *self.progress.lock().unwrap() = Some(Arc::clone(tracker));
+    }
+
+    fn progress(&self) -> Option<(u64, u64)> {
+        self.progress
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|tracker| (tracker.position(), tracker.total()))
+    }
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Arc<RunContext>>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn with_context<R>(f: impl FnOnce(Option<&RunContext>) -> R) -> R {
+    CONTEXT.with(|context| f(context.borrow().as_deref()))
+}
+
+#[derive(Clone, Debug)]
+pub struct Runner {
+    budget: Option<Duration>,
+    grace: Duration,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self {
+            budget: None,
+            grace: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Runner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runner with the budget from [`BUDGET_ENV`], if set.
+    pub fn from_env() -> Result<Self, AocError> {
+        let runner = Self::new();
+        match std::env::var(BUDGET_ENV) {
+            Ok(value) => Ok(runner.budget(parse_budget(&value)?)),
+            Err(_) => Ok(runner),
+        }
+    }
+
+    /// Time the solution may take before it is cancelled.
+    pub fn budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// How long to wait for a cancelled solution to stop before giving up
+    /// on it and leaving its thread behind.
+    pub fn grace(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    pub fn run(&self, input: &str, process: Solution) -> Result<String, AocError> {
+        self.run_with(input, process)
+    }
+
+    /// Like [`run`](Self::run), for a closure that returns more than the
+    /// answer, such as the times of its steps.
+    pub fn run_with<T, F>(&self, input: &str, process: F) -> Result<T, AocError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&str) -> Result<T, AocError> + Send + 'static,
+    {
+        let Some(budget) = self.budget else {
+            return process(input);
+        };
+
+        let context = Arc::new(RunContext {
+            token: CancellationToken::new(),
+            progress: Mutex::new(None),
+        });
+        let (sender, receiver) = mpsc::channel();
+        let input: Arc<str> = input.into();
+        let start = Instant::now();
+
+        let handle = thread::Builder::new()
+            .name("aoc-solution".to_string())
+            .spawn({
+                let context = Arc::clone(&context);
+                move || {
+                    CONTEXT.with(|current| *current.borrow_mut() = Some(context));
+                    // The runner may have stopped listening, which is fine.
+                    let _ = sender.send(process(&input));
+                }
+            })?;
+
+        let result = match receiver.recv_timeout(budget) {
+            Ok(result) => return result,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                context.token.cancel();
+                receiver.recv_timeout(self.grace)
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(mpsc::RecvTimeoutError::Disconnected)
+            }
+        };
+
+        match result {
+            // Finished just after the deadline without checking the token.
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(AocError::Cancelled))
+            | Err(mpsc::RecvTimeoutError::Timeout) => Err(AocError::TimedOut {
+                budget,
+                elapsed: start.elapsed(),
+                progress: context.progress(),
+            }),
+            Ok(Err(error)) => Err(error),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                // The solution panicked; surface it as if run directly.
+                match handle.join() {
+                    Err(panic) => std::panic::resume_unwind(panic),
+                    Ok(()) => unreachable!("solution thread exited without a result"),
+                }
+            }
+        }
+    }
+}
+
+fn parse_budget(value: &str) -> Result<Duration, AocError> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| AocError::InvalidBudget {
+            value: value.to_string(),
+        })
+}
+
+/// Entry point for the bins: draws progress bars in a terminal and applies
+/// the budget from [`BUDGET_ENV`].
+pub fn run(input: &str, process: Solution) -> Result<String, AocError> {
+    progress::enable();
+    Runner::from_env()?.run(input, process)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cancel, progress::Progress};
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn quick(input: &str) -> Result<String, AocError> {
+        Ok(input.len().to_string())
+    }
+
+    fn cooperative(_input: &str) -> Result<String, AocError> {
+        let token = cancel::current();
+        let progress = Progress::new(1_000_000);
+        for _ in 0..1_000_000 {
+            token.check()?;
+            progress.inc(1);
+            thread::sleep(Duration::from_micros(50));
+        }
+        Ok("finished".to_string())
+    }
+
+    fn stubborn(_input: &str) -> Result<String, AocError> {
+        thread::sleep(Duration::from_secs(5));
+        Ok("finished".to_string())
+    }
+
+    fn panics(_input: &str) -> Result<String, AocError> {
+        panic!("solution panicked")
+    }
+
+    #[test]
+    fn test_run_without_budget() -> miette::Result<()> {
+        assert_eq!("5", Runner::new().run("hello", quick)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_within_budget() -> miette::Result<()> {
+        let runner = Runner::new().budget(Duration::from_secs(10));
+        assert_eq!("5", runner.run("hello", quick)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cooperative_timeout_reports_progress() {
+        let runner = Runner::new().budget(Duration::from_millis(50));
+        match runner.run("", cooperative) {
+            Err(AocError::TimedOut {
+                budget,
+                elapsed,
+                progress: Some((done, total)),
+            }) => {
+                assert_eq!(Duration::from_millis(50), budget);
+                assert!(elapsed >= budget);
+                assert!(done > 0 && done < total, "{done}/{total}");
+            }
+            other => panic!("expected a timeout, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_stubborn_solution_is_abandoned() {
+        let runner = Runner::new()
+            .budget(Duration::from_millis(10))
+            .grace(Duration::from_millis(10));
+        let start = Instant::now();
+        assert!(matches!(
+            runner.run("", stubborn),
+            Err(AocError::TimedOut { progress: None, .. })
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "solution panicked")]
+    fn test_panic_is_propagated() {
+        let _ = Runner::new().budget(Duration::from_secs(10)).run("", panics);
+    }
+
+    #[rstest]
+    #[case("2", Duration::from_secs(2))]
+    #[case(" 0.25\n", Duration::from_millis(250))]
+    fn test_parse_budget(#[case] value: &str, #[case] expected: Duration) -> miette::Result<()> {
+        assert_eq!(expected, parse_budget(value)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("5s")]
+    #[case("-1")]
+    fn test_parse_invalid_budget(#[case] value: &str) {
+        assert!(matches!(
+            parse_budget(value),
+            Err(AocError::InvalidBudget { .. })
+        ));
+    }
+}
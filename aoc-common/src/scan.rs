@@ -0,0 +1,358 @@
+//! Fast scanning of puzzle input: newline positions and fixed-width
+//! ASCII integers.
+//!
+//! Newlines are found a block at a time. On `x86_64` a block is 16 bytes
+//! compared with SSE2 (part of the baseline instruction set, so no runtime
+//! detection is needed); every other target falls back to SWAR on 8-byte
+//! words. The [`scalar`] module holds the byte-at-a-time reference
+//! implementations the accelerated paths are tested against.
+
+/// Comparison of one block of input against `b'\n'`.
+trait Block {
+    const WIDTH: usize;
+    /// Bits of the match mask used by each byte of the block.
+    const BITS_PER_BYTE: u32;
+
+    /// Returns a mask with a bit set for every newline in `chunk`, which is
+    /// exactly [`Block::WIDTH`] bytes long.
+    fn newline_mask(chunk: &[u8]) -> u64;
+}
+
+/// Widest block of any implementation, used to pad the tail of the input.
+const MAX_WIDTH: usize = 16;
+
+/// SIMD within a register: eight bytes compared at once using plain `u64`
+/// arithmetic, which every target supports.
+#[cfg_attr(target_arch = "x86_64", allow(dead_code))]
+struct Swar;
+
+impl Block for Swar {
+    const WIDTH: usize = 8;
+    const BITS_PER_BYTE: u32 = 8;
+
+    #[inline(always)]
+    fn newline_mask(chunk: &[u8]) -> u64 {
+        const LOW_7: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+        const NEWLINES: u64 = 0x0A0A_0A0A_0A0A_0A0A;
+
+        let word = u64::from_le_bytes(
+            chunk.try_into().expect("chunk is one block wide"),
+        ) ^ NEWLINES;
+        // Sets the high bit of every zero byte. Unlike the shorter
+        // `(x - 0x01..) & !x` trick no borrow crosses between bytes, so the
+        // mask has no false positives above the first match.
+        !(((word & LOW_7) + LOW_7) | word | LOW_7)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+struct Sse2;
+
+#[cfg(target_arch = "x86_64")]
+impl Block for Sse2 {
+    const WIDTH: usize = 16;
+    const BITS_PER_BYTE: u32 = 1;
+
+    #[inline(always)]
+    fn newline_mask(chunk: &[u8]) -> u64 {
+        use std::arch::x86_64::{
+            _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8,
+            _mm_set1_epi8,
+        };
+
+        assert_eq!(chunk.len(), Self::WIDTH);
+        // SAFETY: SSE2 is always available on x86_64, the load is
+        // unaligned and `chunk` is 16 bytes long.
+        let bits = unsafe {
+            let bytes = _mm_loadu_si128(chunk.as_ptr().cast());
+            let hits = _mm_cmpeq_epi8(bytes, _mm_set1_epi8(b'\n' as i8));
+            _mm_movemask_epi8(hits)
+        };
+        u64::from(bits as u16)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+type Native = Sse2;
+#[cfg(not(target_arch = "x86_64"))]
+type Native = Swar;
+
+/// Iterator over the byte offsets of every `b'\n'`, see [`newlines`].
+pub struct Newlines<'a> {
+    inner: Scanner<'a, Native>,
+}
+
+impl Iterator for Newlines<'_> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        self.inner.next()
+    }
+}
+
+struct Scanner<'a, B> {
+    bytes: &'a [u8],
+    base: usize,
+    mask: u64,
+    block: std::marker::PhantomData<B>,
+}
+
+impl<'a, B: Block> Scanner<'a, B> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            base: 0,
+            mask: Self::load(bytes, 0),
+            block: std::marker::PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    fn load(bytes: &[u8], base: usize) -> u64 {
+        match bytes.get(base..base + B::WIDTH) {
+            Some(chunk) => B::newline_mask(chunk),
+            None => {
+                // Zero padding never matches, so the short tail can reuse
+                // the block comparison.
+                let mut tail = [0u8; MAX_WIDTH];
+                let rest = &bytes[base.min(bytes.len())..];
+                tail[..rest.len()].copy_from_slice(rest);
+                B::newline_mask(&tail[..B::WIDTH])
+            }
+        }
+    }
+}
+
+impl<B: Block> Iterator for Scanner<'_, B> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.mask != 0 {
+                let offset = self.mask.trailing_zeros() / B::BITS_PER_BYTE;
+                self.mask &= self.mask - 1;
+                return Some(self.base + offset as usize);
+            }
+            self.base += B::WIDTH;
+            if self.base >= self.bytes.len() {
+                return None;
+            }
+            self.mask = Self::load(self.bytes, self.base);
+        }
+    }
+}
+
+/// Byte offsets of every `b'\n'` in `bytes`, in ascending order.
+pub fn newlines(bytes: &[u8]) -> Newlines<'_> {
+    Newlines {
+        inner: Scanner::new(bytes),
+    }
+}
+
+/// Iterator over the lines of a string, see [`lines`].
+pub struct Lines<'a> {
+    input: &'a str,
+    newlines: Newlines<'a>,
+    start: usize,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self.newlines.next() {
+            Some(end) => {
+                // `\n` is ASCII, so both ends are char boundaries.
+                let line = &self.input[self.start..end];
+                self.start = end + 1;
+                Some(line.strip_suffix('\r').unwrap_or(line))
+            }
+            None if self.start < self.input.len() => {
+                let line = &self.input[self.start..];
+                self.start = self.input.len();
+                Some(line)
+            }
+            None => None,
+        }
+    }
+}
+
+/// Drop-in replacement for [`str::lines`] driven by [`newlines`]: splits on
+/// `\n` and `\r\n`, and yields no empty line for a trailing line ending.
+pub fn lines(input: &str) -> Lines<'_> {
+    Lines {
+        input,
+        newlines: newlines(input.as_bytes()),
+        start: 0,
+    }
+}
+
+/// Parses up to eight ASCII digits as one number without branching on each
+/// byte. Spaces count as zero, so right-aligned columns such as `" 6"` can
+/// be passed as-is.
+///
+/// # Panics
+///
+/// If `digits` is longer than eight bytes.
+#[inline]
+pub fn parse_fixed(digits: &[u8]) -> u64 {
+    assert!(digits.len() <= 8, "at most eight digits fit in one word");
+    let mut word = [b'0'; 8];
+    word[8 - digits.len()..].copy_from_slice(digits);
+
+    // Keeps 0-9 for digits and 0 for spaces, then folds neighbouring
+    // lanes together: pairs of digits, then quads, then all eight.
+    let mut value = u64::from_le_bytes(word) & 0x0F0F_0F0F_0F0F_0F0F;
+    value = value.wrapping_mul(10).wrapping_add(value >> 8)
+        & 0x00FF_00FF_00FF_00FF;
+    value = value.wrapping_mul(100).wrapping_add(value >> 16)
+        & 0x0000_FFFF_0000_FFFF;
+    value.wrapping_mul(10_000).wrapping_add(value >> 32) & 0xFFFF_FFFF
+}
+
+/// Byte-at-a-time reference implementations of the scanners above.
+pub mod scalar {
+    /// Byte offsets of every `b'\n'` in `bytes`.
+    pub fn newlines(bytes: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        bytes
+            .iter()
+            .enumerate()
+            .filter(|(_, &b)| b == b'\n')
+            .map(|(idx, _)| idx)
+    }
+
+    /// Parses ASCII digits, counting spaces as zero.
+    pub fn parse_fixed(digits: &[u8]) -> u64 {
+        digits.iter().fold(0, |acc, &b| match b {
+            b' ' => acc * 10,
+            _ => acc * 10 + u64::from(b - b'0'),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn random_text(len: usize, seed: u64) -> String {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        (0..len)
+            .map(|_| match rng.u8(0..10) {
+                0 => '\n',
+                1 => '\r',
+                2 => ' ',
+                _ => rng.alphanumeric(),
+            })
+            .collect()
+    }
+
+    fn scan<B: Block>(input: &str) -> Vec<usize> {
+        Scanner::<B>::new(input.as_bytes()).collect()
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("\n")]
+    #[case("no newline at all")]
+    #[case("a\nb\n")]
+    #[case("\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n")]
+    #[case("exactly sixteen\n")]
+    #[case("fifteen bytes..\nsixteen bytes...\n")]
+    #[case("unicode ✓\nlines\r\n")]
+    fn test_newlines_match_scalar(#[case] input: &str) {
+        let expected: Vec<usize> = scalar::newlines(input.as_bytes()).collect();
+        assert_eq!(expected, newlines(input.as_bytes()).collect::<Vec<_>>());
+        assert_eq!(expected, scan::<Swar>(input));
+        #[cfg(target_arch = "x86_64")]
+        assert_eq!(expected, scan::<Sse2>(input));
+    }
+
+    #[test]
+    fn test_newlines_match_scalar_random() {
+        for len in 0..300 {
+            let input = random_text(len, len as u64);
+            let expected: Vec<usize> =
+                scalar::newlines(input.as_bytes()).collect();
+            assert_eq!(expected, scan::<Swar>(&input), "len {len}");
+            #[cfg(target_arch = "x86_64")]
+            assert_eq!(expected, scan::<Sse2>(&input), "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_swar_has_no_false_positives() {
+        // 0x0B is `\n ^ 0x01`, the byte the short zero-byte trick reports
+        // after a real match.
+        let input = "\n\x0b\x0b\n\x0b\x0b\x0b\x0b";
+        assert_eq!(vec![0, 3], scan::<Swar>(input));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("\n")]
+    #[case("\n\n")]
+    #[case("one")]
+    #[case("one\ntwo")]
+    #[case("one\ntwo\n")]
+    #[case("one\r\ntwo\r\n")]
+    #[case("bare\rreturn\r")]
+    #[case("trailing blank\n\n")]
+    fn test_lines_match_std(#[case] input: &str) {
+        assert_eq!(
+            input.lines().collect::<Vec<_>>(),
+            lines(input).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_lines_match_std_random() {
+        for len in 0..300 {
+            let input = random_text(len, len as u64);
+            assert_eq!(
+                input.lines().collect::<Vec<_>>(),
+                lines(&input).collect::<Vec<_>>(),
+                "len {len}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(b"", 0)]
+    #[case(b"0", 0)]
+    #[case(b"7", 7)]
+    #[case(b" 6", 6)]
+    #[case(b"41", 41)]
+    #[case(b"80414", 80414)]
+    #[case(b"  123", 123)]
+    #[case(b"12345678", 12_345_678)]
+    #[case(b"99999999", 99_999_999)]
+    fn test_parse_fixed(#[case] digits: &[u8], #[case] expected: u64) {
+        assert_eq!(expected, parse_fixed(digits));
+        assert_eq!(expected, scalar::parse_fixed(digits));
+    }
+
+    #[test]
+    fn test_parse_fixed_matches_scalar_random() {
+        let mut rng = fastrand::Rng::with_seed(2024);
+        for _ in 0..10_000 {
+            let width = rng.usize(1..=8);
+            let value = rng.u64(0..10u64.pow(width as u32));
+            let digits = format!("{value:>width$}");
+            assert_eq!(value, parse_fixed(digits.as_bytes()), "{digits:?}");
+            assert_eq!(
+                scalar::parse_fixed(digits.as_bytes()),
+                parse_fixed(digits.as_bytes())
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at most eight digits")]
+    fn test_parse_fixed_too_wide() {
+        parse_fixed(b"123456789");
+    }
+}
@@ -0,0 +1,230 @@
+//! Registry of a day's solution variants.
+//!
+//! Each day's `lib.rs` lists its variants once with
+//! [`solutions!`](crate::solutions), which declares the day's
+//! `SOLUTIONS`, its [example tests](crate::example_tests), its
+//! [recorded-answer tests](crate::answer_tests) and its
+//! [budget tests](crate::budget_tests). Tooling that
+//! runs every variant, such as the `verify` bins, works from that list.
+
+use std::fmt;
+
+use crate::{runner::Solution, AocError};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Variant {
+    /// 1 or 2.
+    pub part: u8,
+    /// Module name of the variant, e.g. `part1_nom`.
+    pub name: &'static str,
+    pub process: Solution,
+    /// Too slow to benchmark, like a brute force kept as a baseline.
+    pub slow: bool,
+    /// The parse and solve steps of `process`, for variants that build a
+    /// model of the input before solving it.
+    pub stages: Option<Stages>,
+}
+
+/// Runs the two halves of a variant separately, so the benchmarks can tell
+/// a slow parser from a slow algorithm. The model is built and dropped
+/// inside, since it usually borrows from the input.
+#[derive(Clone, Copy, Debug)]
+pub struct Stages {
+    /// Parses the input and drops the model.
+    pub parse: fn(&str) -> Result<(), AocError>,
+    /// Parses the input, then calls back with the solve step on the parsed
+    /// model, which can be run as often as needed.
+    pub with_solver: WithSolver,
+}
+
+/// Parses an input and hands its solve step to a callback.
+pub type WithSolver = fn(&str, &mut dyn FnMut(&Solver)) -> Result<(), AocError>;
+
+/// The solve step of a variant on an already parsed model.
+pub type Solver<'a> = dyn Fn() -> Result<String, AocError> + Sync + 'a;
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// Declares a day's `SOLUTIONS` and tests per variant against its part's
+/// example, recorded answer and time budget, and counts the allocations of
+/// its tests (see [`allocations`](crate::allocations)), e.g. in a day's
+/// `lib.rs`:
+///
+/// ```ignore
+/// aoc_common::solutions! {
+///     budgets: 1 => 500 us, 2 => 5 ms;
+///     part1: 1 => crate::part1::process_sequential,
+///     #[cfg(feature = "parallel")]
+///     part1_parallel: 1 => crate::part1::process_parallel,
+///     part2: 2 => crate::part2::process {
+///         parse: crate::part2::parse,
+///         solve: crate::part2::solve,
+///     },
+///     part2_brute_force: 2 => crate::part2_brute_force::process [slow],
+/// }
+/// ```
+///
+/// Attributes such as `cfg` apply to the variant and its tests. `[slow]`
+/// marks a variant the [benchmarks](crate::divan_benches) and
+/// [time budgets](crate::budgets) leave out. A variant that parses the input
+/// into a model before solving it can name its two steps,
+/// `parse: fn(&str) -> Result<Model, AocError>` and
+/// `solve: fn(&Model) -> Result<String, AocError>`, to have them
+/// benchmarked on their own as well.
+///
+/// Before the variants, in any order:
+///
+/// - `budgets: part => amount unit, ...;` sets the median time every
+///   variant of a part may take on the real input, in `s`, `ms` or `us`,
+///   declaring `BUDGETS`. Parts without one aren't checked.
+/// - `scale_input: path;` names the [`Scaler`](crate::bench_harness::Scaler)
+///   the benchmarks generate larger inputs with, if repeating the input's
+///   lines wouldn't keep it valid.
+#[macro_export]
+macro_rules! solutions {
+    ($($registry:tt)*) => {
+        $crate::__solutions! {
+            @options [$crate::bench_harness::repeat_lines] []
+            $($registry)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __solutions {
+    (
+        @options [$old:path] [$($budgets:tt)*]
+        scale_input: $scale:path; $($rest:tt)*
+    ) => {
+        $crate::__solutions! { @options [$scale] [$($budgets)*] $($rest)* }
+    };
+    (
+        @options [$scale:path] [$($old:tt)*]
+        budgets: $($part:literal => $amount:literal $unit:ident),+ $(,)?; $($rest:tt)*
+    ) => {
+        $crate::__solutions! {
+            @options [$scale] [$($part => $amount $unit),+]
+            $($rest)*
+        }
+    };
+    (
+        @options [$scale:path] [$($budget_part:literal => $amount:literal $unit:ident),*]
+        $(
+            $(#[$attr:meta])* $name:ident: $part:literal => $process:path
+            $({ parse: $parse:path, solve: $solve:path $(,)? })?
+            $([$flag:ident])?
+        ),* $(,)?
+    ) => {
+        /// The day's directory, where its inputs are.
+        pub const DAY_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+        /// Generates larger inputs for the scaling benchmarks.
+        pub static SCALE_INPUT: $crate::bench_harness::Scaler = $scale;
+
+        /// Median time the variants of each part may take on the real input.
+        pub static BUDGETS: &[$crate::budgets::PartBudget] = &[
+            $(
+                $crate::budgets::PartBudget {
+                    part: $budget_part,
+                    median: $crate::__duration!($amount $unit),
+                },
+            )*
+        ];
+
+        /// Every variant of this day's solutions.
+        pub static SOLUTIONS: &[$crate::solutions::Variant] = &[
+            $(
+                $(#[$attr])*
+                $crate::solutions::Variant {
+                    part: $part,
+                    name: stringify!($name),
+                    process: $process,
+                    slow: $crate::__variant_flag!($($flag)?),
+                    stages: $crate::__stages!($({ parse: $parse, solve: $solve })?),
+                },
+            )*
+        ];
+
+        /// Lets the day's tests hold variants to an allocation budget.
+        #[cfg(test)]
+        #[global_allocator]
+        static ALLOCATOR: $crate::allocations::CountingAllocator =
+            $crate::allocations::CountingAllocator;
+
+        $crate::example_tests! {
+            $($(#[$attr])* $name: $part => $process),*
+        }
+
+        $crate::answer_tests! {
+            $($(#[$attr])* $name: $part => $process $([$flag])?),*
+        }
+
+        $crate::budget_tests! {
+            $($(#[$attr])* $name),*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __variant_flag {
+    () => {
+        false
+    };
+    (slow) => {
+        true
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __stages {
+    () => {
+        None
+    };
+    ({ parse: $parse:path, solve: $solve:path }) => {
+        Some($crate::solutions::Stages {
+            parse: |input| $parse(input).map(|model| {
+                ::std::hint::black_box(model);
+            }),
+            with_solver: |input, run| {
+                let model = $parse(input)?;
+                run(&|| $solve(&model));
+                Ok(())
+            },
+        })
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(input: &str) -> Result<Vec<&str>, AocError> {
+        Ok(input.split_whitespace().collect())
+    }
+
+    fn solve(words: &[&str]) -> Result<String, AocError> {
+        Ok(words.len().to_string())
+    }
+
+    #[test]
+    fn test_stages() -> miette::Result<()> {
+        let stages: Stages = crate::__stages!({ parse: parse, solve: solve }).unwrap();
+        (stages.parse)("a b c")?;
+
+        let mut answers = vec![];
+        (stages.with_solver)("a b c", &mut |solve| {
+            answers.push(solve().unwrap());
+            answers.push(solve().unwrap());
+        })?;
+        assert_eq!(vec!["3", "3"], answers);
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+//! Puzzle inputs kept encrypted in the repository, as Advent of Code asks
+//! that inputs aren't published.
+//!
+//! `input.txt` is committed as `input.txt.enc`, sealed with
+//! ChaCha20-Poly1305 under a key that stays outside the repository (see
+//! [`KeySources`]). [`read_input`] decrypts the sealed copy whenever there
+//! is one, even if a plain (git-ignored) copy sits next to it, so every
+//! run reads what is committed; only an input not sealed yet, such as a
+//! download made without a key, is read in the clear.
+//! Days read their inputs through [`crate::input`].
+//!
+//! The nonce is derived from the key and the plaintext, so sealing the same
+//! input again produces the same file and doesn't show up as a change.
+
+use std::{
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, OsRng},
+    ChaCha20Poly1305, Nonce,
+};
+use sha2::{Digest, Sha256};
+
+use crate::AocError;
+
+/// Environment variable holding the key as 64 hex digits.
+pub const KEY_ENV: &str = "AOC_INPUT_KEY";
+const KEY_FILE_NAME: &str = "adventofcode.key";
+/// Appended to an input's file name when sealed.
+pub const SEALED_EXTENSION: &str = "enc";
+/// Leads every sealed file, for the format to be recognisable and
+/// versioned.
+const MAGIC: &[u8; 5] = b"AOCV1";
+const NONCE_LEN: usize = 12;
+
+/// Key inputs are sealed with. Its `Debug` output is redacted.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Key([u8; 32]);
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+impl Key {
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().as_bytes();
+        if hex.len() != 64 {
+            return None;
+        }
+        let mut key = [0; 32];
+        for (byte, pair) in key.iter_mut().zip(hex.chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+        }
+        Some(Self(key))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// The key of the current user.
+    pub fn find() -> Result<Self, AocError> {
+        KeySources::from_env().find()
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+/// Places the key can be read from, in order of precedence:
+///
+/// 1. the `AOC_INPUT_KEY` environment variable (how CI provides it)
+/// 2. `.adventofcode.key` in the home directory
+/// 3. `adventofcode.key` in the user's config directory
+#[derive(Debug, Default)]
+pub struct KeySources {
+    pub env: Option<String>,
+    pub home_dir: Option<PathBuf>,
+    pub config_dir: Option<PathBuf>,
+}
+
+impl KeySources {
+    pub fn from_env() -> Self {
+        Self {
+            env: std::env::var(KEY_ENV).ok(),
+            home_dir: dirs::home_dir(),
+            config_dir: dirs::config_dir(),
+        }
+    }
+
+    /// Where a new key is written by `aoc-tool vault keygen`.
+    pub fn default_file(&self) -> Option<PathBuf> {
+        self.config_dir.as_ref().map(|dir| dir.join(KEY_FILE_NAME))
+    }
+
+    pub fn find(&self) -> Result<Key, AocError> {
+        let home_file = self
+            .home_dir
+            .as_ref()
+            .map(|dir| dir.join(format!(".{KEY_FILE_NAME}")));
+        let config_file = self.default_file();
+
+        let from_file = |path: &Option<PathBuf>| {
+            path.as_ref().and_then(|path| fs::read_to_string(path).ok())
+        };
+        let (origin, hex) = if let Some(hex) = self.env.clone() {
+            (format!("${KEY_ENV}"), hex)
+        } else if let Some(hex) = from_file(&home_file) {
+            (home_file.unwrap().display().to_string(), hex)
+        } else if let Some(hex) = from_file(&config_file) {
+            (config_file.unwrap().display().to_string(), hex)
+        } else {
+            return Err(AocError::MissingInputKey {
+                searched: [format!("${KEY_ENV}")]
+                    .into_iter()
+                    .chain(
+                        [home_file, config_file]
+                            .into_iter()
+                            .flatten()
+                            .map(|path| path.display().to_string()),
+                    )
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            });
+        };
+        Key::from_hex(&hex).ok_or(AocError::InvalidInputKey { origin })
+    }
+}
+
+/// Encrypts an input.
+pub fn seal(key: &Key, plaintext: &[u8]) -> Vec<u8> {
+    let digest = Sha256::new()
+        .chain_update(key.0)
+        .chain_update(plaintext)
+        .finalize();
+    let nonce = Nonce::from_slice(&digest[..NONCE_LEN]);
+    let ciphertext = key
+        .cipher()
+        .encrypt(nonce, plaintext)
+        .expect("inputs are far below ChaCha20-Poly1305's message limit");
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(nonce);
+    sealed.extend_from_slice(&ciphertext);
+    sealed
+}
+
+/// Decrypts an input sealed with [`seal`]; `None` if it wasn't sealed with
+/// this key or has been tampered with.
+pub fn open(key: &Key, sealed: &[u8]) -> Option<String> {
+    let rest = sealed.strip_prefix(MAGIC)?;
+    if rest.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let plaintext = key
+        .cipher()
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()?;
+    String::from_utf8(plaintext).ok()
+}
+
+pub fn sealed_path(plain: &Path) -> PathBuf {
+    let mut name = plain.as_os_str().to_owned();
+    name.push(".");
+    name.push(SEALED_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Reads `name` in `day_dir`: `name.enc` decrypted with the user's key if
+/// it exists, otherwise the plain file.
+pub fn read_input(day_dir: &Path, name: &str) -> Result<String, AocError> {
+    read_input_with(&KeySources::from_env(), day_dir, name)
+}
+
+fn read_input_with(sources: &KeySources, day_dir: &Path, name: &str) -> Result<String, AocError> {
+    let plain = day_dir.join(name);
+    let sealed_path = sealed_path(&plain);
+    if !sealed_path.exists() {
+        return Ok(fs::read_to_string(plain)?);
+    }
+    let sealed = fs::read(&sealed_path)?;
+    open(&sources.find()?, &sealed).ok_or_else(|| AocError::UndecryptableInput {
+        path: sealed_path.display().to_string(),
+    })
+}
+
+/// Seals `name` in `day_dir` to `name.enc` next to it, returning the path
+/// written.
+pub fn seal_file(key: &Key, day_dir: &Path, name: &str) -> Result<PathBuf, AocError> {
+    let plain = day_dir.join(name);
+    let sealed_path = sealed_path(&plain);
+    fs::write(&sealed_path, seal(key, &fs::read(plain)?))?;
+    Ok(sealed_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn key() -> Key {
+        Key::from_hex(KEY).unwrap()
+    }
+
+    #[test]
+    fn test_key_hex_round_trip() {
+        assert_eq!(KEY, key().to_hex());
+        assert_eq!(Some(key()), Key::from_hex(&format!("  {KEY}\n")));
+        assert_eq!(None, Key::from_hex(&KEY[2..]));
+        assert_eq!(None, Key::from_hex(&KEY.replace('0', "g")));
+        assert_eq!("Key(..)", format!("{:?}", key()));
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let input = "3   4\n4   3\n";
+        let sealed = seal(&key(), input.as_bytes());
+        assert!(!sealed.windows(5).any(|window| window == b"3   4"));
+        assert_eq!(Some(input.to_string()), open(&key(), &sealed));
+        assert_eq!(sealed, seal(&key(), input.as_bytes()), "sealing is deterministic");
+        assert_ne!(sealed, seal(&key(), b"3   4\n4   4\n"));
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_key_and_tampering() {
+        let sealed = seal(&key(), b"secret input");
+        assert_eq!(None, open(&Key::generate(), &sealed));
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(None, open(&key(), &tampered));
+        assert_eq!(None, open(&key(), &sealed[..10]));
+        assert_eq!(None, open(&key(), b"secret input"));
+    }
+
+    #[test]
+    fn test_read_input_prefers_sealed_copy() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = KeySources {
+            env: Some(KEY.to_string()),
+            ..KeySources::default()
+        };
+        fs::write(dir.path().join("example1.txt"), "plain").unwrap();
+        assert_eq!("plain", read_input_with(&sources, dir.path(), "example1.txt")?);
+
+        fs::write(dir.path().join("example1.txt.enc"), seal(&key(), b"sealed")).unwrap();
+        assert_eq!("sealed", read_input_with(&sources, dir.path(), "example1.txt")?);
+        assert!(matches!(
+            read_input_with(&KeySources::default(), dir.path(), "example1.txt"),
+            Err(AocError::MissingInputKey { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_seal_file_writes_next_to_input() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("input1.txt"), "1abc2\n").unwrap();
+        let path = seal_file(&key(), dir.path(), "input1.txt")?;
+        assert_eq!(dir.path().join("input1.txt.enc"), path);
+        assert_eq!(
+            Some("1abc2\n".to_string()),
+            open(&key(), &fs::read(path).unwrap())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_input_is_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            read_input(dir.path(), "input1.txt"),
+            Err(AocError::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_precedence() -> miette::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let other = Key::generate();
+        fs::write(dir.path().join(".adventofcode.key"), other.to_hex()).unwrap();
+        fs::write(dir.path().join("adventofcode.key"), "not a key").unwrap();
+
+        let mut sources = KeySources {
+            env: Some(KEY.to_string()),
+            home_dir: Some(dir.path().to_path_buf()),
+            config_dir: Some(dir.path().to_path_buf()),
+        };
+        assert_eq!(key(), sources.find()?);
+
+        sources.env = None;
+        assert_eq!(other, sources.find()?);
+
+        sources.home_dir = None;
+        assert!(matches!(
+            sources.find(),
+            Err(AocError::InvalidInputKey { origin }) if origin.ends_with("adventofcode.key")
+        ));
+
+        sources.config_dir = Some(dir.path().join("nowhere"));
+        assert!(matches!(
+            sources.find(),
+            Err(AocError::MissingInputKey { searched }) if searched.starts_with("$AOC_INPUT_KEY")
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+//! Runs every variant of a day against every [`InputSet`] and checks the
+//! answers, for each day's `verify` bin (`just verify day-N`). Bins that
+//! install the [`CountingAllocator`](crate::allocations::CountingAllocator)
+//! also get the peak heap of every run.
+
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    allocations::{self, format_bytes},
+    input_sets::InputSet,
+    runner::{Runner, BUDGET_ENV},
+    solutions::Variant,
+    AocError,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String, actual: String },
+    /// The variant returned an error or panicked.
+    Error(String),
+    /// The set has no expected answer for the part.
+    Unchecked { actual: String },
+    /// Ran out of time, which fails like a wrong answer: a solution that
+    /// hangs on someone's input doesn't work for them.
+    TimedOut,
+    /// Ran out of time, but the variant is marked `[slow]`, like a brute
+    /// force baseline, so it isn't counted as a failure.
+    Slow,
+    /// The input couldn't be read without a key.
+    Skipped,
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Fail { .. } | Self::Error(_) | Self::TimedOut)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => f.write_str("pass"),
+            Self::Fail { expected, actual } => {
+                write!(f, "FAIL: expected {expected}, got {actual}")
+            }
+            Self::Error(error) => write!(f, "ERROR: {error}"),
+            Self::Unchecked { actual } => write!(f, "unchecked: got {actual}"),
+            Self::TimedOut => f.write_str("TIMED OUT"),
+            Self::Slow => f.write_str("timed out, marked slow"),
+            Self::Skipped => f.write_str("skipped, no input key"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub set: String,
+    pub part: u8,
+    pub variant: &'static str,
+    pub status: Status,
+    pub elapsed: Duration,
+    /// Most heap in use at once during the run, in bytes, if allocations
+    /// are counted.
+    pub peak: Option<u64>,
+}
+
+/// Runs every variant on every input set of the day in `day_dir`.
+pub fn verify(
+    day_dir: &Path,
+    solutions: &[Variant],
+    runner: &Runner,
+) -> Result<Vec<Outcome>, AocError> {
+    let mut outcomes = Vec::new();
+    for set in InputSet::load_all(day_dir)? {
+        for variant in solutions {
+            let mut peak = None;
+            let mut elapsed = Duration::ZERO;
+            let status = match set.input(variant.part) {
+                Ok(input) => {
+                    let expected = set.answers.get(&variant.part);
+                    // Timed from here, like `runner`, so reading and
+                    // decrypting the input doesn't count against the solution.
+                    let start = Instant::now();
+                    let result = if allocations::installed() {
+                        let (result, bytes) =
+                            allocations::measure_peak(|| run(runner, &input, variant));
+                        peak = Some(bytes);
+                        result
+                    } else {
+                        run(runner, &input, variant)
+                    };
+                    elapsed = start.elapsed();
+                    judge(result, expected.map(String::as_str), variant.slow)
+                }
+                Err(AocError::MissingInputKey { .. }) => Status::Skipped,
+                Err(error) => Status::Error(error.to_string()),
+            };
+            outcomes.push(Outcome {
+                set: set.name.clone(),
+                part: variant.part,
+                variant: variant.name,
+                status,
+                elapsed,
+                peak,
+            });
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Status of a run against the expected answer, if there is one. Timeouts
+/// only pass for variants marked `slow`.
+pub(crate) fn judge(
+    result: Result<String, Option<String>>,
+    expected: Option<&str>,
+    slow: bool,
+) -> Status {
+    match (result, expected) {
+        (Ok(actual), Some(expected)) if actual == expected => Status::Pass,
+        (Ok(actual), Some(expected)) => Status::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+        (Ok(actual), None) => Status::Unchecked { actual },
+        (Err(None), _) if slow => Status::Slow,
+        (Err(None), _) => Status::TimedOut,
+        (Err(Some(error)), _) => Status::Error(error),
+    }
+}
+
+/// Runs one variant, turning errors and panics into a message, or `None`
+/// if it timed out.
+pub(crate) fn run(runner: &Runner, input: &str, variant: &Variant) -> Result<String, Option<String>> {
+    match panic::catch_unwind(AssertUnwindSafe(|| runner.run(input, variant.process))) {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(AocError::TimedOut { .. })) => Err(None),
+        Ok(Err(error)) => Err(Some(error.to_string())),
+        Err(panic) => Err(Some(
+            panic_message(&*panic)
+                .map_or_else(|| "panicked".to_string(), |message| format!("panicked: {message}")),
+        )),
+    }
+}
+
+/// Message a solution panicked with, if it's a string.
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> Option<String> {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+}
+
+/// Formats outcomes as an aligned table, one row per run.
+pub fn report(outcomes: &[Outcome]) -> String {
+    let set_width = outcomes
+        .iter()
+        .map(|outcome| outcome.set.len())
+        .chain(["set".len()])
+        .max()
+        .unwrap_or_default();
+    let variant_width = outcomes
+        .iter()
+        .map(|outcome| outcome.variant.len())
+        .chain(["variant".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!(
+        "{:set_width$}  part  {:variant_width$}  {:>10}  {:>10}  result\n",
+        "set", "variant", "time", "peak"
+    );
+    for outcome in outcomes {
+        table.push_str(&format!(
+            "{:set_width$}  {:>4}  {:variant_width$}  {:>10}  {:>10}  {}\n",
+            outcome.set,
+            outcome.part,
+            outcome.variant,
+            format!("{:.2?}", outcome.elapsed),
+            outcome.peak.map_or_else(|| "-".to_string(), format_bytes),
+            outcome.status
+        ));
+    }
+    table
+}
+
+/// Time each run gets unless `AOC_TIME_BUDGET` says otherwise.
+pub const DEFAULT_BUDGET: Duration = Duration::from_secs(10);
+
+/// Entry point of the `verify` bins: prints the table and fails if any
+/// variant got an answer wrong, errored or, unless it's slow, timed out on
+/// any set.
+pub fn main(day_dir: &str, solutions: &[Variant]) -> miette::Result<()> {
+    let runner = match std::env::var(BUDGET_ENV) {
+        Ok(_) => Runner::from_env()?,
+        Err(_) => Runner::new().budget(DEFAULT_BUDGET),
+    };
+    let outcomes = verify(Path::new(day_dir), solutions, &runner)?;
+    print!("{}", report(&outcomes));
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.status.is_failure())
+        .count();
+    if failed > 0 {
+        return Err(AocError::VerificationFailed {
+            failed,
+            total: outcomes.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::input_sets::{ANSWERS_FILE, SETS_DIR};
+    use pretty_assertions::assert_eq;
+
+    fn length(input: &str) -> Result<String, AocError> {
+        Ok(input.len().to_string())
+    }
+
+    fn first_line(input: &str) -> Result<String, AocError> {
+        Ok(input.lines().next().unwrap_or_default().to_string())
+    }
+
+    fn unfinished(_input: &str) -> Result<String, AocError> {
+        todo!("part 2")
+    }
+
+    fn slow(_input: &str) -> Result<String, AocError> {
+        let token = crate::cancel::current();
+        loop {
+            token.check()?;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    const SOLUTIONS: &[Variant] = &[
+        Variant {
+            part: 1,
+            name: "part1",
+            process: length,
+            slow: false,
+            stages: None,
+        },
+        Variant {
+            part: 1,
+            name: "part1_lines",
+            process: first_line,
+            slow: false,
+            stages: None,
+        },
+        Variant {
+            part: 2,
+            name: "part2",
+            process: unfinished,
+            slow: false,
+            stages: None,
+        },
+    ];
+
+    #[test]
+    fn test_timeout_fails_unless_slow() -> miette::Result<()> {
+        let day = tempfile::tempdir().unwrap();
+        fs::write(day.path().join("input.txt"), "abc").unwrap();
+        let solutions = [
+            Variant {
+                part: 2,
+                name: "part2",
+                process: slow,
+                slow: false,
+                stages: None,
+            },
+            Variant {
+                part: 2,
+                name: "part2_brute_force",
+                process: slow,
+                slow: true,
+                stages: None,
+            },
+        ];
+        let runner = Runner::new().budget(Duration::from_millis(20));
+
+        let outcomes = verify(day.path(), &solutions, &runner)?;
+        assert_eq!(Status::TimedOut, outcomes[0].status);
+        assert!(outcomes[0].status.is_failure());
+        assert_eq!(Status::Slow, outcomes[1].status);
+        assert!(!outcomes[1].status.is_failure());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_every_variant_on_every_set() -> miette::Result<()> {
+        let day = tempfile::tempdir().unwrap();
+        fs::write(day.path().join("input.txt"), "abc").unwrap();
+        let alice = day.path().join(SETS_DIR).join("alice");
+        fs::create_dir_all(&alice).unwrap();
+        fs::write(alice.join("input.txt"), "12\n3").unwrap();
+        fs::write(alice.join(ANSWERS_FILE), "1 4\n2 x\n").unwrap();
+
+        let outcomes = verify(day.path(), SOLUTIONS, &Runner::new())?;
+        let statuses = outcomes
+            .iter()
+            .map(|outcome| (outcome.set.as_str(), outcome.variant, outcome.status.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("default", "part1", Status::Unchecked { actual: "3".to_string() }),
+                ("default", "part1_lines", Status::Unchecked { actual: "abc".to_string() }),
+                ("default", "part2", Status::Error("panicked: not yet implemented: part 2".to_string())),
+                ("alice", "part1", Status::Pass),
+                (
+                    "alice",
+                    "part1_lines",
+                    Status::Fail {
+                        expected: "4".to_string(),
+                        actual: "12".to_string()
+                    }
+                ),
+                ("alice", "part2", Status::Error("panicked: not yet implemented: part 2".to_string())),
+            ],
+            statuses
+        );
+
+        let table = report(&outcomes);
+        assert!(table.starts_with("set      part  variant"), "{table}");
+        assert!(table.contains("alice       1  part1_lines"), "{table}");
+        Ok(())
+    }
+}
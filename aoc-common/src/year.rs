@@ -0,0 +1,465 @@
+//! Runs a whole year, every part of every day, and adds up the time, for
+//! each year's `year` bin (`just run-year`).
+//!
+//! Each part runs its fastest variant, the one with the lowest latest median
+//! in the year's bench history, or its first registered variant if it was
+//! never benchmarked; `--all` runs every variant instead. Variants marked
+//! `[slow]` are left out unless a part has nothing else. With `--parallel`
+//! the days run on rayon's thread pool, and each day is still timed on the
+//! thread that runs it, so its time doesn't include waiting for the others.
+
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use rayon::prelude::*;
+
+use crate::{
+    answers::{self, Manifest},
+    bench_history::{History, Key, HISTORY_FILE},
+    input,
+    integrity::Fingerprint,
+    output::{self, Format, Record},
+    runner::Runner,
+    solutions::Variant,
+    verify::{self, Status},
+    AocError,
+};
+
+/// A day crate of the year, as listed with [`days!`](crate::days).
+#[derive(Clone, Copy, Debug)]
+pub struct Day {
+    /// The day's `day-N` directory.
+    pub dir: &'static str,
+    pub solutions: &'static [Variant],
+}
+
+impl Day {
+    /// N of the day's `day-N` directory.
+    pub fn number(&self) -> u8 {
+        answers::day_of(Path::new(self.dir))
+            .unwrap_or_else(|| panic!("{} is not a day-N directory", self.dir))
+    }
+}
+
+/// Lists the days of a year crate for [`main`], e.g.
+/// `aoc_common::days![day_1, day_2]`.
+#[macro_export]
+macro_rules! days {
+    ($($day:ident),* $(,)?) => {
+        &[$($crate::year::Day {
+            dir: $day::DAY_DIR,
+            solutions: $day::SOLUTIONS,
+        }),*]
+    };
+}
+
+/// Which variants of each part to run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selection {
+    Fastest,
+    All,
+}
+
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub record: Record,
+    /// The answer checked against the year's `answers.txt`.
+    pub status: Status,
+}
+
+impl Run {
+    /// Time of the run, not counting reading the input.
+    pub fn elapsed(&self) -> Duration {
+        self.record.timings.map_or(Duration::ZERO, |timings| timings.total())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DayRuns {
+    pub day: u8,
+    pub runs: Vec<Run>,
+}
+
+impl DayRuns {
+    pub fn elapsed(&self) -> Duration {
+        self.runs.iter().map(Run::elapsed).sum()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct YearRuns {
+    pub days: Vec<DayRuns>,
+    pub parallel: bool,
+    /// Time from the first run to the last, less than the total when the
+    /// days ran in parallel.
+    pub wall: Duration,
+}
+
+impl YearRuns {
+    /// Time of every run added up.
+    pub fn total(&self) -> Duration {
+        self.days.iter().map(DayRuns::elapsed).sum()
+    }
+
+    pub fn runs(&self) -> impl Iterator<Item = &Run> {
+        self.days.iter().flat_map(|day| &day.runs)
+    }
+
+    pub fn records(&self) -> Vec<Record> {
+        self.runs().map(|run| run.record.clone()).collect()
+    }
+}
+
+/// Variants of `day` to run, part by part.
+pub fn select(
+    day: u8,
+    solutions: &'static [Variant],
+    selection: Selection,
+    medians: &BTreeMap<Key, Duration>,
+) -> Vec<&'static Variant> {
+    let mut parts = solutions.iter().map(|variant| variant.part).collect::<Vec<_>>();
+    parts.sort();
+    parts.dedup();
+    parts
+        .into_iter()
+        .flat_map(|part| {
+            let of_part = solutions.iter().filter(move |variant| variant.part == part);
+            let mut candidates = of_part.clone().filter(|variant| !variant.slow).collect::<Vec<_>>();
+            if candidates.is_empty() {
+                candidates = of_part.collect();
+            }
+            match selection {
+                Selection::All => candidates,
+                Selection::Fastest => candidates
+                    .into_iter()
+                    .min_by_key(|variant| {
+                        let key = Key {
+                            day,
+                            part,
+                            variant: variant.name.to_string(),
+                        };
+                        medians.get(&key).copied().unwrap_or(Duration::MAX)
+                    })
+                    .into_iter()
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Runs `variants` of `day` one after the other, reading each part's input
+/// once, before its first run.
+pub fn run_day(day: &Day, variants: &[&Variant], manifest: &Manifest, runner: &Runner) -> DayRuns {
+    let number = day.number();
+    let day_dir = Path::new(day.dir);
+    let mut inputs = BTreeMap::new();
+    let runs = variants
+        .iter()
+        .map(|variant| {
+            let input = inputs.entry(variant.part).or_insert_with(|| {
+                input::read(day_dir, variant.part).map(|input| {
+                    let hash = Fingerprint::of(&input).sha256;
+                    (input, hash)
+                })
+            });
+            let outcome;
+            let (input_hash, result) = match &*input {
+                Ok((input, hash)) => {
+                    outcome = output::run(runner, input, variant);
+                    (Some(hash.clone()), outcome.as_ref())
+                }
+                Err(error) => (None, Err(error)),
+            };
+            let expected = manifest.get(number, variant.part);
+            let status = match result {
+                Ok((answer, _)) => verify::judge(Ok(answer.clone()), expected, variant.slow),
+                Err(AocError::TimedOut { .. }) => verify::judge(Err(None), expected, variant.slow),
+                Err(AocError::MissingInputKey { .. }) => Status::Skipped,
+                Err(error) => Status::Error(error.to_string()),
+            };
+            Run {
+                record: Record::new(day_dir, variant, input_hash, result),
+                status,
+            }
+        })
+        .collect();
+    DayRuns { day: number, runs }
+}
+
+/// Runs the selected variants of every day of the year in `year_dir`.
+pub fn run_year(
+    year_dir: &Path,
+    days: &[Day],
+    selection: Selection,
+    parallel: bool,
+    runner: &Runner,
+) -> Result<YearRuns, AocError> {
+    let manifest = Manifest::load(year_dir)?;
+    let medians = History::load(&year_dir.join(HISTORY_FILE))?.latest_medians();
+    let run = |day: &Day| {
+        let variants = select(day.number(), day.solutions, selection, &medians);
+        run_day(day, &variants, &manifest, runner)
+    };
+
+    if parallel {
+        // Starts the pool's threads before the clock.
+        rayon::current_num_threads();
+    }
+    let start = Instant::now();
+    let days = if parallel {
+        days.par_iter().map(run).collect()
+    } else {
+        days.iter().map(run).collect()
+    };
+    Ok(YearRuns {
+        days,
+        parallel,
+        wall: start.elapsed(),
+    })
+}
+
+/// Formats the runs as an aligned table, one row per run and one per day,
+/// with the total of the year.
+pub fn report(year: &YearRuns) -> String {
+    let variant_width = year
+        .runs()
+        .map(|run| run.record.variant.len())
+        .chain(["wall time".len()])
+        .max()
+        .unwrap_or_default();
+    let answer_width = year
+        .runs()
+        .map(|run| run.record.answer.as_ref().map_or(1, String::len))
+        .chain(["answer".len()])
+        .max()
+        .unwrap_or_default();
+    let row = |day: &str, part: &str, variant: &str, answer: &str, time: &str, result: &str| {
+        let row = format!("{day:>3}  {part:>4}  {variant:variant_width$}  {answer:>answer_width$}  {time:>10}  {result}");
+        format!("{}\n", row.trim_end())
+    };
+    let time = |elapsed: Duration| format!("{elapsed:.2?}");
+
+    let mut table = row("day", "part", "variant", "answer", "time", "result");
+    for day in &year.days {
+        for run in &day.runs {
+            table.push_str(&row(
+                &day.day.to_string(),
+                &run.record.part.to_string(),
+                run.record.variant,
+                run.record.answer.as_deref().unwrap_or("-"),
+                &time(run.elapsed()),
+                &run.status.to_string(),
+            ));
+        }
+        table.push_str(&row(&day.day.to_string(), "", "day total", "", &time(day.elapsed()), ""));
+    }
+    let runs = year.runs().count();
+    table.push_str(&row(
+        "",
+        "",
+        "total",
+        "",
+        &time(year.total()),
+        &format!("{} days, {runs} runs", year.days.len()),
+    ));
+    if year.parallel {
+        table.push_str(&row("", "", "wall time", "", &time(year.wall), "days in parallel"));
+    }
+    table
+}
+
+/// Runs every part of every day of the year and times them.
+#[derive(Debug, Parser)]
+struct Args {
+    /// Runs every variant of each part rather than the fastest.
+    #[arg(long)]
+    all: bool,
+    /// Runs the days in parallel on rayon's thread pool.
+    #[arg(long)]
+    parallel: bool,
+}
+
+/// Entry point of the `year` bins: prints the table, or the record of every
+/// run in the [format](output::Format) from `AOC_OUTPUT`, and fails if any
+/// run got an answer other than the one in `answers.txt`, or errored. Runs
+/// get no time budget unless `AOC_TIME_BUDGET` sets one.
+pub fn main(year_dir: &str, days: &[Day]) -> miette::Result<()> {
+    let args = Args::parse();
+    let format = Format::from_env()?;
+    let selection = if args.all {
+        Selection::All
+    } else {
+        Selection::Fastest
+    };
+    let year = run_year(
+        Path::new(year_dir),
+        days,
+        selection,
+        args.parallel,
+        &Runner::from_env()?,
+    )?;
+    match format {
+        Format::Text => print!("{}", report(&year)),
+        format => print!("{}", output::format(format, &year.records())),
+    }
+    let failed = year.runs().filter(|run| run.status.is_failure()).count();
+    if failed > 0 {
+        return Err(AocError::VerificationFailed {
+            failed,
+            total: year.runs().count(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::answers::MANIFEST;
+    use pretty_assertions::assert_eq;
+
+    fn length(input: &str) -> Result<String, AocError> {
+        Ok(input.len().to_string())
+    }
+
+    fn first_line(input: &str) -> Result<String, AocError> {
+        Ok(input.lines().next().unwrap_or_default().to_string())
+    }
+
+    fn variant(part: u8, name: &'static str, process: crate::runner::Solution, slow: bool) -> Variant {
+        Variant {
+            part,
+            name,
+            process,
+            slow,
+            stages: None,
+        }
+    }
+
+    fn solutions() -> &'static [Variant] {
+        Box::leak(Box::new([
+            variant(1, "part1", length, false),
+            variant(1, "part1_lines", first_line, false),
+            variant(2, "part2_brute_force", length, true),
+            variant(2, "part2", first_line, false),
+        ]))
+    }
+
+    fn hangs(_input: &str) -> Result<String, AocError> {
+        let token = crate::cancel::current();
+        loop {
+            token.check()?;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn names(variants: &[&Variant]) -> Vec<&'static str> {
+        variants.iter().map(|variant| variant.name).collect()
+    }
+
+    #[test]
+    fn test_select() {
+        let solutions = solutions();
+        assert_eq!(
+            vec!["part1", "part2"],
+            names(&select(1, solutions, Selection::Fastest, &BTreeMap::new()))
+        );
+        let medians = BTreeMap::from([
+            (Key::of("day_1_bench::solutions::part1").unwrap(), Duration::from_micros(20)),
+            (Key::of("day_1_bench::solutions::part1_lines").unwrap(), Duration::from_micros(10)),
+        ]);
+        assert_eq!(
+            vec!["part1_lines", "part2"],
+            names(&select(1, solutions, Selection::Fastest, &medians))
+        );
+        assert_eq!(
+            vec!["part1", "part1_lines", "part2"],
+            names(&select(1, solutions, Selection::All, &medians))
+        );
+        // A part with only a slow variant still runs it.
+        assert_eq!(
+            vec!["part1_lines", "part2_brute_force"],
+            names(&select(1, &solutions[..3], Selection::Fastest, &medians))
+        );
+    }
+
+    #[test]
+    fn test_run_year() -> miette::Result<()> {
+        let year_dir = tempfile::tempdir().unwrap();
+        fs::write(year_dir.path().join(MANIFEST), "1 1 5\n1 2 xyz\n").unwrap();
+        let mut days = vec![];
+        for (day, input) in [(1, "abc\nd"), (2, "12")] {
+            let dir = year_dir.path().join(format!("day-{day}"));
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("input.txt"), input).unwrap();
+            days.push(Day {
+                dir: dir.to_str().unwrap().to_string().leak(),
+                solutions: solutions(),
+            });
+        }
+
+        for parallel in [false, true] {
+            let year = run_year(year_dir.path(), &days, Selection::Fastest, parallel, &Runner::new())?;
+            let statuses = year
+                .days
+                .iter()
+                .flat_map(|day| day.runs.iter().map(|run| (day.day, run.record.variant, run.status.clone())))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vec![
+                    (1, "part1", Status::Pass),
+                    (
+                        1,
+                        "part2",
+                        Status::Fail {
+                            expected: "xyz".to_string(),
+                            actual: "abc".to_string()
+                        }
+                    ),
+                    (2, "part1", Status::Unchecked { actual: "2".to_string() }),
+                    (2, "part2", Status::Unchecked { actual: "12".to_string() }),
+                ],
+                statuses
+            );
+            assert_eq!(year.total(), year.days[0].elapsed() + year.days[1].elapsed());
+
+            let table = report(&year);
+            assert!(table.starts_with("day  part  variant    answer        time  result\n"), "{table}");
+            assert!(table.contains("  1     1  part1           5"), "{table}");
+            assert!(table.contains("  1        day total"), "{table}");
+            assert!(table.contains("total  "), "{table}");
+            assert!(table.contains("2 days, 4 runs"), "{table}");
+            assert_eq!(parallel, table.contains("days in parallel"), "{table}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_timeout_fails_unless_slow() -> miette::Result<()> {
+        let year_dir = tempfile::tempdir().unwrap();
+        let dir = year_dir.path().join("day-1");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("input.txt"), "abc").unwrap();
+        let days = [Day {
+            dir: dir.to_str().unwrap().to_string().leak(),
+            solutions: Box::leak(Box::new([
+                variant(1, "part1", hangs, false),
+                variant(2, "part2_brute_force", hangs, true),
+            ])),
+        }];
+
+        let runner = Runner::new().budget(Duration::from_millis(20));
+        let year = run_year(year_dir.path(), &days, Selection::Fastest, false, &runner)?;
+        let statuses = year.runs().map(|run| run.status.clone()).collect::<Vec<_>>();
+        assert_eq!(vec![Status::TimedOut, Status::Slow], statuses);
+        assert_eq!(1, year.runs().filter(|run| run.status.is_failure()).count());
+        Ok(())
+    }
+}